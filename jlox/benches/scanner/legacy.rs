@@ -0,0 +1,413 @@
+//! Frozen copy of the `Peekable<Chars>` scanner that `Scanner<'src>` replaced,
+//! kept only as the baseline for the scanner benchmark.
+
+use jlox::errors::scan_error::ScanError;
+use jlox::scanner::token::{Span, TokenType};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// The old literal shape, which owned its string contents.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum Literal {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Nil,
+}
+
+/// The old token shape, which owned a freshly allocated lexeme.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Token {
+    pub token_type: TokenType,
+    pub lexeme: String,
+    pub literal: Option<Literal>,
+    pub span: Span,
+}
+
+impl Token {
+    pub fn new(
+        token_type: TokenType,
+        lexeme: String,
+        literal: Option<Literal>,
+        span: Span,
+    ) -> Self {
+        Token {
+            token_type,
+            lexeme,
+            literal,
+            span,
+        }
+    }
+}
+
+pub struct Scanner {
+    source: String,
+}
+
+/// Everything a scan produced: the tokens (always terminated by `Eof`) and every
+/// lexical error found along the way.
+#[allow(dead_code)]
+pub struct ScanResult {
+    pub tokens: Vec<Token>,
+    pub errors: Vec<ScanError>,
+}
+
+/// Position the scanner was at when it started a token.
+#[derive(Clone, Copy)]
+struct Mark {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Character cursor over the source that keeps track of the byte offset,
+/// line and column of the next character.
+pub struct Cursor<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(source: &'a str) -> Self {
+        Cursor {
+            chars: source.char_indices().peekable(),
+            len: source.len(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.chars.clone().nth(1).map(|(_, c)| c)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let (_, c) = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |&(i, _)| i)
+    }
+
+    fn mark(&mut self) -> Mark {
+        Mark {
+            offset: self.offset(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn span_from(&mut self, mark: Mark) -> Span {
+        Span::new(mark.offset, self.offset(), mark.line, mark.column)
+    }
+}
+
+impl Scanner {
+    pub fn new(source: String) -> Self {
+        Scanner { source }
+    }
+
+    pub fn scan_tokens(&self) -> ScanResult {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut errors: Vec<ScanError> = Vec::new();
+        let mut cursor = Cursor::new(&self.source);
+
+        // scan_token always consumes the text it reports an error for, so the
+        // scan simply carries on with whatever follows.
+        loop {
+            match self.scan_token(&mut cursor) {
+                Ok(Some(token)) => tokens.push(token),
+                Ok(None) => break,
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let start = cursor.mark();
+        tokens.push(Token::new(
+            TokenType::Eof,
+            "".to_string(),
+            None,
+            cursor.span_from(start),
+        ));
+        ScanResult { tokens, errors }
+    }
+
+    pub fn scan_token(&self, cursor: &mut Cursor) -> Result<Option<Token>, ScanError> {
+        while let Some(c) = cursor.peek() {
+            let start = cursor.mark();
+            match c {
+                '(' => return Ok(Some(self.make_token(TokenType::LeftParen, cursor, start))),
+                ')' => return Ok(Some(self.make_token(TokenType::RightParen, cursor, start))),
+                '{' => return Ok(Some(self.make_token(TokenType::LeftBrace, cursor, start))),
+                '}' => return Ok(Some(self.make_token(TokenType::RightBrace, cursor, start))),
+                ',' => return Ok(Some(self.make_token(TokenType::Comma, cursor, start))),
+                '.' => return Ok(Some(self.make_token(TokenType::Dot, cursor, start))),
+                '-' => return Ok(Some(self.make_token(TokenType::Minus, cursor, start))),
+                '+' => return Ok(Some(self.make_token(TokenType::Plus, cursor, start))),
+                ';' => return Ok(Some(self.make_token(TokenType::Semicolon, cursor, start))),
+                '*' => return Ok(Some(self.make_token(TokenType::Star, cursor, start))),
+                '!' => {
+                    return Ok(Some(self.match_token(
+                        '=',
+                        TokenType::BangEqual,
+                        TokenType::Bang,
+                        cursor,
+                        start,
+                    )))
+                }
+                '=' => {
+                    return Ok(Some(self.match_token(
+                        '=',
+                        TokenType::EqualEqual,
+                        TokenType::Equal,
+                        cursor,
+                        start,
+                    )))
+                }
+                '<' => {
+                    return Ok(Some(self.match_token(
+                        '=',
+                        TokenType::LessEqual,
+                        TokenType::Less,
+                        cursor,
+                        start,
+                    )))
+                }
+                '>' => {
+                    return Ok(Some(self.match_token(
+                        '=',
+                        TokenType::GreaterEqual,
+                        TokenType::Greater,
+                        cursor,
+                        start,
+                    )))
+                }
+                '/' => {
+                    cursor.next();
+                    if let Some('/') = cursor.peek() {
+                        while let Some(c) = cursor.peek() {
+                            if c == '\n' {
+                                break;
+                            }
+                            cursor.next();
+                        }
+                    } else {
+                        return Ok(Some(Token::new(
+                            TokenType::Slash,
+                            "/".to_string(),
+                            None,
+                            cursor.span_from(start),
+                        )));
+                    }
+                }
+                ' ' | '\r' | '\t' | '\n' => {
+                    cursor.next();
+                }
+                '"' => return self.string(cursor, start).map(Some),
+                _ => {
+                    return if c.is_alphabetic() {
+                        Ok(Some(self.identifier(cursor, start)))
+                    } else if c.is_ascii_digit() {
+                        Ok(Some(self.number(cursor, start)))
+                    } else {
+                        cursor.next();
+                        Err(ScanError::new(
+                            cursor.span_from(start),
+                            format!("Unexpected character: {}", c),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn match_token(
+        &self,
+        expected: char,
+        if_match: TokenType,
+        if_not: TokenType,
+        cursor: &mut Cursor,
+        start: Mark,
+    ) -> Token {
+        let mut lexeme = cursor.next().unwrap().to_string();
+        if cursor.peek() == Some(expected) {
+            lexeme.push(cursor.next().unwrap());
+            return Token::new(if_match, lexeme, None, cursor.span_from(start));
+        }
+        Token::new(if_not, lexeme, None, cursor.span_from(start))
+    }
+
+    fn make_token(&self, token_type: TokenType, cursor: &mut Cursor, start: Mark) -> Token {
+        let lexeme = cursor.next().unwrap().to_string();
+        Token::new(token_type, lexeme, None, cursor.span_from(start))
+    }
+
+    fn identifier(&self, cursor: &mut Cursor, start: Mark) -> Token {
+        let mut lexeme = String::new();
+        while let Some(c) = cursor.peek() {
+            if c.is_alphanumeric() {
+                lexeme.push(cursor.next().unwrap());
+            } else {
+                break;
+            }
+        }
+        let token_type = match lexeme.as_str() {
+            "and" => TokenType::And,
+            "class" => TokenType::Class,
+            "else" => TokenType::Else,
+            "false" => TokenType::False,
+            "for" => TokenType::For,
+            "fun" => TokenType::Fun,
+            "if" => TokenType::If,
+            "nil" => TokenType::Nil,
+            "or" => TokenType::Or,
+            "print" => TokenType::Print,
+            "return" => TokenType::Return,
+            "super" => TokenType::Super,
+            "this" => TokenType::This,
+            "true" => TokenType::True,
+            "var" => TokenType::Var,
+            "while" => TokenType::While,
+            _ => TokenType::Identifier,
+        };
+        let literal = match token_type {
+            TokenType::False => Some(Literal::Bool(false)),
+            TokenType::True => Some(Literal::Bool(true)),
+            TokenType::Nil => Some(Literal::Nil),
+            _ => None,
+        };
+        Token::new(token_type, lexeme, literal, cursor.span_from(start))
+    }
+
+    fn string(&self, cursor: &mut Cursor, start: Mark) -> Result<Token, ScanError> {
+        let mut value = String::new();
+        let mut error = None;
+        cursor.next(); // Consume the opening quote
+        loop {
+            match cursor.peek() {
+                None => {
+                    return Err(ScanError::new(
+                        cursor.span_from(start),
+                        "Unterminated string.",
+                    ))
+                }
+                Some('"') => break,
+                Some('\\') => {
+                    let escape_start = cursor.mark();
+                    cursor.next();
+                    match self.escape(cursor) {
+                        Ok(c) => value.push(c),
+                        Err(message) => {
+                            // Keep scanning to the closing quote so the rest of the
+                            // string is not mistaken for code.
+                            error.get_or_insert(ScanError::new(
+                                cursor.span_from(escape_start),
+                                message,
+                            ));
+                        }
+                    }
+                }
+                Some(_) => value.push(cursor.next().unwrap()),
+            }
+        }
+        cursor.next(); // Consume the closing quote
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let span = cursor.span_from(start);
+        Ok(Token::new(
+            TokenType::String,
+            self.source[span.start..span.end].to_string(),
+            Some(Literal::String(value)),
+            span,
+        ))
+    }
+
+    // escape -> "\\" ( "n" | "t" | "\"" | "\\" | "u{" HEX_DIGIT{1,6} "}" )
+    fn escape(&self, cursor: &mut Cursor) -> Result<char, String> {
+        match cursor.next() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('u') => self.unicode_escape(cursor),
+            Some(c) => Err(format!("Unknown escape sequence '\\{}'.", c)),
+            None => Err("Unterminated string.".to_string()),
+        }
+    }
+
+    fn unicode_escape(&self, cursor: &mut Cursor) -> Result<char, String> {
+        if cursor.peek() != Some('{') {
+            return Err("Expect '{' after '\\u'.".to_string());
+        }
+        cursor.next();
+
+        let mut hex = String::new();
+        while let Some(c) = cursor.peek() {
+            if c.is_ascii_hexdigit() {
+                hex.push(cursor.next().unwrap());
+            } else {
+                break;
+            }
+        }
+        if cursor.peek() != Some('}') {
+            return Err("Expect '}' after unicode escape digits.".to_string());
+        }
+        cursor.next();
+
+        if hex.is_empty() || hex.len() > 6 {
+            return Err("Unicode escape must have 1 to 6 hex digits.".to_string());
+        }
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("'\\u{{{}}}' is not a valid Unicode scalar value.", hex))
+    }
+
+    // number -> DIGIT+ ( "." DIGIT+ )?
+    fn number(&self, cursor: &mut Cursor, start: Mark) -> Token {
+        let mut lexeme = String::new();
+        self.digits(cursor, &mut lexeme);
+
+        // Look for a fractional part; a trailing '.' is left for the Dot token.
+        if cursor.peek() == Some('.') && cursor.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+            lexeme.push(cursor.next().unwrap());
+            self.digits(cursor, &mut lexeme);
+        }
+
+        let value = lexeme.parse::<f64>().expect("scanned number is a valid f64");
+        Token::new(
+            TokenType::Number,
+            lexeme,
+            Some(Literal::Number(value)),
+            cursor.span_from(start),
+        )
+    }
+
+    fn digits(&self, cursor: &mut Cursor, lexeme: &mut String) {
+        while let Some(c) = cursor.peek() {
+            if c.is_ascii_digit() {
+                lexeme.push(cursor.next().unwrap());
+            } else {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Compares the byte-indexed `Scanner<'src>` against the old `Peekable<Chars>`
+//! scanner on a large generated script. Run with `cargo bench --bench scanner`.
+
+mod legacy;
+
+use jlox::scanner::scanner::Scanner;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SNIPPET: &str = r#"// Generated configuration block
+var total = (count + 12.5) * factor / 3;
+print "item: \t" + name + "\u{2713}";
+if (total >= limit and !done) { result = total - offset; }
+while (index < 100) { index = index + 1; }
+"#;
+
+const ITERATIONS: u32 = 20;
+
+fn generate(bytes: usize) -> String {
+    let mut source = String::with_capacity(bytes + SNIPPET.len());
+    while source.len() < bytes {
+        source.push_str(SNIPPET);
+    }
+    source
+}
+
+fn measure(name: &str, source: &str, mut scan: impl FnMut(&str) -> usize) -> Duration {
+    // Warm up the allocator and, for the new scanner, the symbol table.
+    black_box(scan(source));
+
+    let start = Instant::now();
+    let mut tokens = 0;
+    for _ in 0..ITERATIONS {
+        tokens = black_box(scan(black_box(source)));
+    }
+    let per_run = start.elapsed() / ITERATIONS;
+
+    let mib_per_sec = source.len() as f64 / per_run.as_secs_f64() / (1024.0 * 1024.0);
+    println!(
+        "{:<28} {:>10.2?} per run  {:>8.1} MiB/s  ({} tokens)",
+        name, per_run, mib_per_sec, tokens
+    );
+    per_run
+}
+
+fn main() {
+    let source = generate(8 * 1024 * 1024);
+    println!("scanning {} KiB, {} iterations\n", source.len() / 1024, ITERATIONS);
+
+    // The old `run` also cloned the token vector before handing it to the parser.
+    let legacy = measure("legacy scan + clone", &source, |source| {
+        let result = legacy::Scanner::new(source.to_string()).scan_tokens();
+        let tokens = result.tokens.clone();
+        tokens.len()
+    });
+    let legacy_scan = measure("legacy scan", &source, |source| {
+        legacy::Scanner::new(source.to_string())
+            .scan_tokens()
+            .tokens
+            .len()
+    });
+    let eager = measure("scan_tokens", &source, |source| {
+        Scanner::new(source).scan_tokens().tokens.len()
+    });
+    measure("lazy iterator", &source, |source| Scanner::new(source).count());
+
+    println!(
+        "\nscan_tokens vs legacy scan: {:.2}x, vs legacy scan + clone: {:.2}x",
+        legacy_scan.as_secs_f64() / eager.as_secs_f64(),
+        legacy.as_secs_f64() / eager.as_secs_f64()
+    );
+}
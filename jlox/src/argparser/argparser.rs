@@ -1,4 +1,8 @@
+use crate::ast::ast_format::AstFormat;
+use crate::dialect::dialect::Dialect;
+use crate::parser::parser::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_HEIGHT};
 use argh::FromArgs;
+use std::path::Path;
 
 #[derive(FromArgs)]
 /// Lox interpreter
@@ -6,8 +10,63 @@ pub struct Args {
     /// script file to run
     #[argh(positional)]
     pub script: Option<String>,
+
+    /// language dialect: strict (default) or extended
+    #[argh(option, default = "Dialect::Strict")]
+    pub dialect: Dialect,
+
+    /// print the parsed program as sexpr, rpn, json or dot instead of running it
+    #[argh(option)]
+    pub emit_ast: Option<AstFormat>,
+
+    /// how deeply statements and expressions may nest, in source and
+    /// --load-ast input alike (default 256)
+    #[argh(option, default = "DEFAULT_MAX_DEPTH")]
+    pub max_depth: usize,
+
+    /// how tall an expression tree may grow, where each operator in a chain
+    /// like 1 + 2 + 3 adds a level (default 1024)
+    #[argh(option, default = "DEFAULT_MAX_HEIGHT")]
+    pub max_height: usize,
+
+    /// run a script written with --emit-ast json instead of Lox source
+    #[argh(switch)]
+    pub load_ast: bool,
 }
 
+/// Parses the command line like `argh::from_env`, but also accepts
+/// `--option=value` for every option.
 pub fn parse_args() -> Args {
-    argh::from_env()
+    let strings: Vec<String> = std::env::args().flat_map(split_option).collect();
+    let cmd = Path::new(&strings[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&strings[0]);
+    let strs: Vec<&str> = strings[1..].iter().map(String::as_str).collect();
+
+    Args::from_args(&[cmd], &strs).unwrap_or_else(|early_exit| {
+        std::process::exit(match early_exit.status {
+            Ok(()) => {
+                println!("{}", early_exit.output);
+                0
+            }
+            Err(()) => {
+                eprintln!(
+                    "{}\nRun {} --help for more information.",
+                    early_exit.output, cmd
+                );
+                1
+            }
+        })
+    })
+}
+
+// `--option=value` -> `--option`, `value`; anything else is left alone.
+fn split_option(arg: String) -> Vec<String> {
+    match arg.split_once('=') {
+        Some((option, value)) if option.starts_with("--") => {
+            vec![option.to_string(), value.to_string()]
+        }
+        _ => vec![arg],
+    }
 }
@@ -0,0 +1,66 @@
+use crate::expression::expression::{Expr, ExprId};
+use crate::scanner::token::Span;
+use crate::statement::statement::{Stmt, StmtId};
+use std::cell::{Ref, RefCell};
+
+struct Node<T> {
+    kind: T,
+    span: Span,
+}
+
+/// Owns every expression and statement parsed in a session. Nodes refer to
+/// their children by id, so an id stays valid, and unique, for as long as the
+/// arena lives, which is what lets later passes key side tables on it.
+///
+/// Nodes are only ever added, so ids from earlier REPL lines stay valid while
+/// new lines are parsed into the same arena. Adding needs no `&mut`, because
+/// functions created at runtime keep running bodies that live here.
+#[derive(Default)]
+pub struct Ast {
+    exprs: RefCell<Vec<Node<Expr>>>,
+    stmts: RefCell<Vec<Node<Stmt>>>,
+}
+
+impl Ast {
+    pub fn new() -> Self {
+        Ast::default()
+    }
+
+    /// Adds `expr`, covering `span` of the source, and returns its id.
+    ///
+    /// Panics if called while a node borrowed with `expr` is still alive.
+    pub fn add_expr(&self, expr: Expr, span: Span) -> ExprId {
+        let mut exprs = self.exprs.borrow_mut();
+        exprs.push(Node { kind: expr, span });
+        ExprId::from_index(exprs.len() - 1)
+    }
+
+    /// Adds `stmt`, covering `span` of the source, and returns its id.
+    ///
+    /// Panics if called while a node borrowed with `stmt` is still alive.
+    pub fn add_stmt(&self, stmt: Stmt, span: Span) -> StmtId {
+        let mut stmts = self.stmts.borrow_mut();
+        stmts.push(Node { kind: stmt, span });
+        StmtId::from_index(stmts.len() - 1)
+    }
+
+    pub fn expr(&self, id: ExprId) -> Ref<'_, Expr> {
+        Ref::map(self.exprs.borrow(), |exprs| &exprs[id.index()].kind)
+    }
+
+    pub fn stmt(&self, id: StmtId) -> Ref<'_, Stmt> {
+        Ref::map(self.stmts.borrow(), |stmts| &stmts[id.index()].kind)
+    }
+
+    /// Where the expression `id` was parsed from, from its first token to its
+    /// last.
+    pub fn expr_span(&self, id: ExprId) -> Span {
+        self.exprs.borrow()[id.index()].span
+    }
+
+    /// Where the statement `id` was parsed from, from its first token to its
+    /// last.
+    pub fn stmt_span(&self, id: StmtId) -> Span {
+        self.stmts.borrow()[id.index()].span
+    }
+}
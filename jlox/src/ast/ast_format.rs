@@ -0,0 +1,71 @@
+use crate::ast::arena::Ast;
+use crate::ast::ast_printer::AstPrinter;
+use crate::ast::dot_printer::DotPrinter;
+use crate::ast::rpn_printer::RpnPrinter;
+use crate::ast::serialize;
+use crate::statement::statement::StmtId;
+use std::fmt;
+use std::str::FromStr;
+
+/// How `--emit-ast` prints the parsed program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstFormat {
+    /// Lisp-style S-expressions, one statement per line.
+    Sexpr,
+    /// Reverse Polish Notation for expressions, one statement per line.
+    Rpn,
+    /// The versioned JSON document `--load-ast` reads back, with node kinds
+    /// and spans.
+    Json,
+    /// A Graphviz DOT digraph.
+    Dot,
+}
+
+impl AstFormat {
+    pub fn print(self, ast: &Ast, statements: &[StmtId]) -> String {
+        match self {
+            AstFormat::Sexpr => {
+                let mut printer = AstPrinter::new(ast);
+                lines(statements.iter().map(|&stmt| printer.print_stmt(stmt)))
+            }
+            AstFormat::Rpn => {
+                let mut printer = RpnPrinter::new(ast);
+                lines(statements.iter().map(|&stmt| printer.print_stmt(stmt)))
+            }
+            AstFormat::Json => serialize::to_json(ast, statements),
+            AstFormat::Dot => DotPrinter::new(ast).print_program(statements),
+        }
+    }
+}
+
+fn lines(lines: impl Iterator<Item = String>) -> String {
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+impl FromStr for AstFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sexpr" => Ok(AstFormat::Sexpr),
+            "rpn" => Ok(AstFormat::Rpn),
+            "json" => Ok(AstFormat::Json),
+            "dot" => Ok(AstFormat::Dot),
+            _ => Err(format!(
+                "unknown AST format '{}', expected 'sexpr', 'rpn', 'json' or 'dot'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AstFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AstFormat::Sexpr => write!(f, "sexpr"),
+            AstFormat::Rpn => write!(f, "rpn"),
+            AstFormat::Json => write!(f, "json"),
+            AstFormat::Dot => write!(f, "dot"),
+        }
+    }
+}
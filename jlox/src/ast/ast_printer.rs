@@ -1,30 +1,297 @@
-use crate::expression::expression::{Expr, ExprVisitor};
-use crate::scanner::token::Token;
+use crate::ast::arena::Ast;
+use crate::expression::expression::{Expr, ExprId, ExprVisitor};
+use crate::scanner::token::{Literal, Token};
+use crate::statement::statement::{Function, StmtId, StmtVisitor};
+use std::rc::Rc;
 
-pub struct AstPrinter;
+pub struct AstPrinter<'a> {
+    ast: &'a Ast,
+}
+
+impl<'a> AstPrinter<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        AstPrinter { ast }
+    }
 
-impl AstPrinter {
-    pub fn new() -> Self {
-        AstPrinter
+    pub fn print(&mut self, expr: ExprId) -> String {
+        expr.accept(self.ast, self)
     }
 
-    pub fn print(&mut self, expr: &Expr) -> String {
-        expr.accept(self)
+    pub fn print_stmt(&mut self, stmt: StmtId) -> String {
+        stmt.accept(self.ast, self)
+    }
+
+    // `(fun name (params) body...)`, leaving out the name for a lambda.
+    fn print_function(&mut self, function: &Function) -> String {
+        let params: Vec<&str> = function.params.iter().map(|p| p.lexeme.as_str()).collect();
+        let mut out = String::from("(fun");
+        if let Some(name) = &function.name {
+            out.push_str(&format!(" {}", name.lexeme));
+        }
+        out.push_str(&format!(" ({})", params.join(" ")));
+        for &statement in &function.body {
+            out.push(' ');
+            out.push_str(&self.print_stmt(statement));
+        }
+        out.push(')');
+        out
     }
 }
-impl ExprVisitor<String> for AstPrinter {
-    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> String {
-        format!("({} {} {})",
-                operator.lexeme,
-                left.accept(self),
-                right.accept(self)
+impl ExprVisitor<String> for AstPrinter<'_> {
+    fn visit_assign_expr(&mut self, _id: ExprId, name: &Token, value: ExprId) -> String {
+        format!("(= {} {})", name.lexeme, self.print(value))
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        format!(
+            "({} {} {})",
+            operator.lexeme,
+            self.print(left),
+            self.print(right)
         )
     }
 
-    fn visit_literal_expr(&mut self, value: &Token) -> String {
+    fn visit_call_expr(
+        &mut self,
+        _id: ExprId,
+        callee: ExprId,
+        _paren: &Token,
+        arguments: &[ExprId],
+    ) -> String {
+        let mut out = format!("(call {}", self.print(callee));
+        for &argument in arguments {
+            out.push(' ');
+            out.push_str(&self.print(argument));
+        }
+        out.push(')');
+        out
+    }
+
+    fn visit_comma_expr(&mut self, _id: ExprId, left: ExprId, right: ExprId) -> String {
+        format!("(, {} {})", self.print(left), self.print(right))
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        _id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> String {
+        format!(
+            "(?: {} {} {})",
+            self.print(condition),
+            self.print(then_branch),
+            self.print(else_branch)
+        )
+    }
+
+    fn visit_function_expr(&mut self, _id: ExprId, function: &Rc<Function>) -> String {
+        self.print_function(function)
+    }
+
+    fn visit_get_expr(&mut self, _id: ExprId, object: ExprId, name: &Token) -> String {
+        format!("(. {} {})", self.print(object), name.lexeme)
+    }
+
+    fn visit_grouping_expr(&mut self, _id: ExprId, expression: ExprId) -> String {
+        format!("(group {})", self.print(expression))
+    }
+
+    fn visit_literal_expr(&mut self, _id: ExprId, value: &Token) -> String {
         match &value.literal {
             Some(literal) => literal.to_string(),
-            None => value.lexeme.clone(),
+            None => value.lexeme.to_string(),
+        }
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        format!(
+            "({} {} {})",
+            operator.lexeme,
+            self.print(left),
+            self.print(right)
+        )
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        _id: ExprId,
+        object: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> String {
+        format!(
+            "(= (. {} {}) {})",
+            self.print(object),
+            name.lexeme,
+            self.print(value)
+        )
+    }
+
+    fn visit_super_expr(&mut self, _id: ExprId, _keyword: &Token, method: &Token) -> String {
+        format!("(super {})", method.lexeme)
+    }
+
+    fn visit_this_expr(&mut self, _id: ExprId, _keyword: &Token) -> String {
+        "this".to_string()
+    }
+
+    fn visit_unary_expr(&mut self, _id: ExprId, operator: &Token, right: ExprId) -> String {
+        format!("({} {})", operator.lexeme, self.print(right))
+    }
+
+    fn visit_variable_expr(&mut self, _id: ExprId, name: &Token) -> String {
+        name.lexeme.to_string()
+    }
+
+    fn visit_interpolation_expr(&mut self, _id: ExprId, parts: &[ExprId]) -> String {
+        let mut out = String::from("(interpolate");
+        for &part in parts {
+            out.push(' ');
+            // Quote the literal segments so their spacing is visible.
+            let segment = match &*self.ast.expr(part) {
+                Expr::Literal {
+                    value:
+                        Token {
+                            literal: Some(Literal::String(segment)),
+                            ..
+                        },
+                } => Some(*segment),
+                _ => None,
+            };
+            match segment {
+                Some(segment) => out.push_str(&format!("{:?}", segment)),
+                None => out.push_str(&self.print(part)),
+            }
+        }
+        out.push(')');
+        out
+    }
+}
+
+impl StmtVisitor<String> for AstPrinter<'_> {
+    fn visit_block_stmt(&mut self, _id: StmtId, statements: &[StmtId]) -> String {
+        let mut out = String::from("(block");
+        for &statement in statements {
+            out.push(' ');
+            out.push_str(&self.print_stmt(statement));
+        }
+        out.push(')');
+        out
+    }
+
+    fn visit_break_stmt(&mut self, _id: StmtId, _keyword: &Token) -> String {
+        "(break)".to_string()
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        _id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> String {
+        let mut out = format!("(class {}", name.lexeme);
+        if let Some(superclass) = superclass {
+            out.push_str(&format!(" < {}", self.print(superclass)));
+        }
+        for method in methods {
+            out.push(' ');
+            out.push_str(&self.print_function(method));
+        }
+        out.push(')');
+        out
+    }
+
+    fn visit_continue_stmt(&mut self, _id: StmtId, _keyword: &Token) -> String {
+        "(continue)".to_string()
+    }
+
+    fn visit_expression_stmt(&mut self, _id: StmtId, expression: ExprId) -> String {
+        format!("(; {})", self.print(expression))
+    }
+
+    fn visit_function_stmt(&mut self, _id: StmtId, function: &Rc<Function>) -> String {
+        self.print_function(function)
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> String {
+        match else_branch {
+            Some(else_branch) => format!(
+                "(if {} {} {})",
+                self.print(condition),
+                self.print_stmt(then_branch),
+                self.print_stmt(else_branch)
+            ),
+            None => format!(
+                "(if {} {})",
+                self.print(condition),
+                self.print_stmt(then_branch)
+            ),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, _id: StmtId, expression: ExprId) -> String {
+        format!("(print {})", self.print(expression))
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _id: StmtId,
+        _keyword: &Token,
+        value: Option<ExprId>,
+    ) -> String {
+        match value {
+            Some(value) => format!("(return {})", self.print(value)),
+            None => "(return)".to_string(),
+        }
+    }
+
+    fn visit_var_stmt(&mut self, _id: StmtId, name: &Token, initializer: Option<ExprId>) -> String {
+        match initializer {
+            Some(initializer) => format!("(var {} = {})", name.lexeme, self.print(initializer)),
+            None => format!("(var {})", name.lexeme),
+        }
+    }
+
+    fn visit_while_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> String {
+        match increment {
+            Some(increment) => format!(
+                "(while {} {} {})",
+                self.print(condition),
+                self.print_stmt(body),
+                self.print(increment)
+            ),
+            None => format!(
+                "(while {} {})",
+                self.print(condition),
+                self.print_stmt(body)
+            ),
         }
     }
 }
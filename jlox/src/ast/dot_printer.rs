@@ -0,0 +1,357 @@
+use crate::ast::arena::Ast;
+use crate::expression::expression::{ExprId, ExprVisitor};
+use crate::scanner::token::{Literal, Token};
+use crate::statement::statement::{Function, StmtId, StmtVisitor};
+use std::fmt::Write;
+use std::rc::Rc;
+
+/// Prints the tree as a Graphviz DOT digraph, for rendering with `dot -Tsvg`.
+/// Each node is labelled with its kind and its operator, name or value, and
+/// edges are labelled with the field they come from.
+///
+/// Graph nodes are named after arena ids (`e3` for an expression, `s5` for a
+/// statement), so the same node keeps its name across printers and passes.
+pub struct DotPrinter<'a> {
+    ast: &'a Ast,
+    out: String,
+}
+
+impl<'a> DotPrinter<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        DotPrinter {
+            ast,
+            out: String::new(),
+        }
+    }
+
+    /// The whole program as one digraph, rooted at a `Program` node.
+    pub fn print_program(mut self, statements: &[StmtId]) -> String {
+        self.out.push_str("digraph ast {\n");
+        // Keep children left to right in source order.
+        self.out.push_str("  ordering=out;\n");
+        self.out
+            .push_str("  node [shape=box, fontname=\"monospace\"];\n");
+        self.node("program", "Program");
+        for &statement in statements {
+            let child = self.stmt(statement);
+            self.edge("program", &child, "");
+        }
+        self.out.push_str("}\n");
+        self.out
+    }
+
+    // Each visit writes the node and its edges, and returns the node's name
+    // for the parent to link to.
+    fn expr(&mut self, expr: ExprId) -> String {
+        expr.accept(self.ast, self)
+    }
+
+    fn stmt(&mut self, stmt: StmtId) -> String {
+        stmt.accept(self.ast, self)
+    }
+
+    fn node(&mut self, name: &str, label: &str) {
+        let _ = writeln!(self.out, "  {} [label=\"{}\"];", name, escape(label));
+    }
+
+    fn edge(&mut self, from: &str, to: &str, label: &str) {
+        if label.is_empty() {
+            let _ = writeln!(self.out, "  {} -> {};", from, to);
+        } else {
+            let _ = writeln!(
+                self.out,
+                "  {} -> {} [label=\"{}\"];",
+                from,
+                to,
+                escape(label)
+            );
+        }
+    }
+
+    // Writes a node for the expression `id` with its children.
+    fn expr_node(&mut self, id: ExprId, label: &str, children: &[(&str, ExprId)]) -> String {
+        let name = format!("e{}", id.index());
+        self.node(&name, label);
+        for &(field, child) in children {
+            let child = self.expr(child);
+            self.edge(&name, &child, field);
+        }
+        name
+    }
+
+    fn stmt_name(id: StmtId) -> String {
+        format!("s{}", id.index())
+    }
+
+    fn function(&mut self, name: &str, function: &Function) {
+        let params: Vec<&str> = function.params.iter().map(|p| p.lexeme.as_str()).collect();
+        let label = match &function.name {
+            Some(function_name) => {
+                format!("Function {}({})", function_name.lexeme, params.join(", "))
+            }
+            None => format!("Function ({})", params.join(", ")),
+        };
+        self.node(name, &label);
+        for &statement in &function.body {
+            let child = self.stmt(statement);
+            self.edge(name, &child, "body");
+        }
+    }
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl ExprVisitor<String> for DotPrinter<'_> {
+    fn visit_assign_expr(&mut self, id: ExprId, name: &Token, value: ExprId) -> String {
+        self.expr_node(id, &format!("Assign {}", name.lexeme), &[("value", value)])
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        let label = format!("Binary {}", operator.lexeme);
+        self.expr_node(id, &label, &[("left", left), ("right", right)])
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        id: ExprId,
+        callee: ExprId,
+        _paren: &Token,
+        arguments: &[ExprId],
+    ) -> String {
+        let name = self.expr_node(id, "Call", &[("callee", callee)]);
+        for &argument in arguments {
+            let child = self.expr(argument);
+            self.edge(&name, &child, "argument");
+        }
+        name
+    }
+
+    fn visit_comma_expr(&mut self, id: ExprId, left: ExprId, right: ExprId) -> String {
+        self.expr_node(id, "Comma", &[("left", left), ("right", right)])
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> String {
+        let children = [
+            ("condition", condition),
+            ("then", then_branch),
+            ("else", else_branch),
+        ];
+        self.expr_node(id, "Conditional", &children)
+    }
+
+    fn visit_function_expr(&mut self, id: ExprId, function: &Rc<Function>) -> String {
+        let name = format!("e{}", id.index());
+        self.function(&name, function);
+        name
+    }
+
+    fn visit_get_expr(&mut self, id: ExprId, object: ExprId, name: &Token) -> String {
+        self.expr_node(id, &format!("Get .{}", name.lexeme), &[("object", object)])
+    }
+
+    fn visit_grouping_expr(&mut self, id: ExprId, expression: ExprId) -> String {
+        self.expr_node(id, "Grouping", &[("expression", expression)])
+    }
+
+    fn visit_literal_expr(&mut self, id: ExprId, value: &Token) -> String {
+        let value = match &value.literal {
+            Some(Literal::String(s)) => format!("{:?}", s.as_str()),
+            Some(literal) => literal.to_string(),
+            None => value.lexeme.to_string(),
+        };
+        self.expr_node(id, &format!("Literal {}", value), &[])
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        let label = format!("Logical {}", operator.lexeme);
+        self.expr_node(id, &label, &[("left", left), ("right", right)])
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        id: ExprId,
+        object: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> String {
+        let label = format!("Set .{}", name.lexeme);
+        self.expr_node(id, &label, &[("object", object), ("value", value)])
+    }
+
+    fn visit_super_expr(&mut self, id: ExprId, _keyword: &Token, method: &Token) -> String {
+        self.expr_node(id, &format!("Super .{}", method.lexeme), &[])
+    }
+
+    fn visit_this_expr(&mut self, id: ExprId, _keyword: &Token) -> String {
+        self.expr_node(id, "This", &[])
+    }
+
+    fn visit_unary_expr(&mut self, id: ExprId, operator: &Token, right: ExprId) -> String {
+        let label = format!("Unary {}", operator.lexeme);
+        self.expr_node(id, &label, &[("right", right)])
+    }
+
+    fn visit_variable_expr(&mut self, id: ExprId, name: &Token) -> String {
+        self.expr_node(id, &format!("Variable {}", name.lexeme), &[])
+    }
+
+    fn visit_interpolation_expr(&mut self, id: ExprId, parts: &[ExprId]) -> String {
+        let name = self.expr_node(id, "Interpolation", &[]);
+        for &part in parts {
+            let child = self.expr(part);
+            self.edge(&name, &child, "part");
+        }
+        name
+    }
+}
+
+impl StmtVisitor<String> for DotPrinter<'_> {
+    fn visit_block_stmt(&mut self, id: StmtId, statements: &[StmtId]) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Block");
+        for &statement in statements {
+            let child = self.stmt(statement);
+            self.edge(&name, &child, "");
+        }
+        name
+    }
+
+    fn visit_break_stmt(&mut self, id: StmtId, _keyword: &Token) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Break");
+        name
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> String {
+        let node = Self::stmt_name(id);
+        self.node(&node, &format!("Class {}", name.lexeme));
+        if let Some(superclass) = superclass {
+            let child = self.expr(superclass);
+            self.edge(&node, &child, "superclass");
+        }
+        // Methods are not arena nodes, so they are named after their class.
+        for (index, method) in methods.iter().enumerate() {
+            let child = format!("{}_m{}", node, index);
+            self.function(&child, method);
+            self.edge(&node, &child, "method");
+        }
+        node
+    }
+
+    fn visit_continue_stmt(&mut self, id: StmtId, _keyword: &Token) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Continue");
+        name
+    }
+
+    fn visit_expression_stmt(&mut self, id: StmtId, expression: ExprId) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Expression");
+        let child = self.expr(expression);
+        self.edge(&name, &child, "");
+        name
+    }
+
+    fn visit_function_stmt(&mut self, id: StmtId, function: &Rc<Function>) -> String {
+        let name = Self::stmt_name(id);
+        self.function(&name, function);
+        name
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "If");
+        let child = self.expr(condition);
+        self.edge(&name, &child, "condition");
+        let child = self.stmt(then_branch);
+        self.edge(&name, &child, "then");
+        if let Some(else_branch) = else_branch {
+            let child = self.stmt(else_branch);
+            self.edge(&name, &child, "else");
+        }
+        name
+    }
+
+    fn visit_print_stmt(&mut self, id: StmtId, expression: ExprId) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Print");
+        let child = self.expr(expression);
+        self.edge(&name, &child, "");
+        name
+    }
+
+    fn visit_return_stmt(&mut self, id: StmtId, _keyword: &Token, value: Option<ExprId>) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "Return");
+        if let Some(value) = value {
+            let child = self.expr(value);
+            self.edge(&name, &child, "value");
+        }
+        name
+    }
+
+    fn visit_var_stmt(&mut self, id: StmtId, name: &Token, initializer: Option<ExprId>) -> String {
+        let node = Self::stmt_name(id);
+        self.node(&node, &format!("Var {}", name.lexeme));
+        if let Some(initializer) = initializer {
+            let child = self.expr(initializer);
+            self.edge(&node, &child, "initializer");
+        }
+        node
+    }
+
+    fn visit_while_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> String {
+        let name = Self::stmt_name(id);
+        self.node(&name, "While");
+        let child = self.expr(condition);
+        self.edge(&name, &child, "condition");
+        let child = self.stmt(body);
+        self.edge(&name, &child, "body");
+        if let Some(increment) = increment {
+            let child = self.expr(increment);
+            self.edge(&name, &child, "increment");
+        }
+        name
+    }
+}
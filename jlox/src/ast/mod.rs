@@ -1 +1,7 @@
-pub(crate) mod ast_printer;
\ No newline at end of file
+pub mod arena;
+pub mod ast_format;
+pub(crate) mod ast_printer;
+pub(crate) mod dot_printer;
+pub(crate) mod rpn_printer;
+pub mod serialize;
+pub mod side_table;
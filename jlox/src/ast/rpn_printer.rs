@@ -0,0 +1,284 @@
+use crate::ast::arena::Ast;
+use crate::expression::expression::{ExprId, ExprVisitor};
+use crate::scanner::token::{Literal, Token, TokenType};
+use crate::statement::statement::{Function, StmtId, StmtVisitor};
+use std::rc::Rc;
+
+/// Prints expressions in Reverse Polish Notation: operands first, then the
+/// operator, so `(1 + 2) * 3` becomes `1 2 + 3 *` and grouping disappears.
+///
+/// RPN has no notation for statements, so they keep their source syntax with
+/// every expression inside them written in RPN. Operators that take a varying
+/// number of operands carry the count, as in `f a b call/2`.
+pub struct RpnPrinter<'a> {
+    ast: &'a Ast,
+}
+
+impl<'a> RpnPrinter<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        RpnPrinter { ast }
+    }
+
+    pub fn print(&mut self, expr: ExprId) -> String {
+        expr.accept(self.ast, self)
+    }
+
+    pub fn print_stmt(&mut self, stmt: StmtId) -> String {
+        stmt.accept(self.ast, self)
+    }
+
+    fn postfix(&mut self, operands: &[ExprId], operator: &str) -> String {
+        let mut out = String::new();
+        for &operand in operands {
+            out.push_str(&self.print(operand));
+            out.push(' ');
+        }
+        out.push_str(operator);
+        out
+    }
+
+    // `name(a, b) { body }`, the common part of declarations, methods and
+    // lambdas.
+    fn print_function(&mut self, function: &Function) -> String {
+        let params: Vec<&str> = function.params.iter().map(|p| p.lexeme.as_str()).collect();
+        let mut out = String::new();
+        if let Some(name) = &function.name {
+            out.push_str(name.lexeme.as_str());
+        }
+        out.push_str(&format!(
+            "({}) {}",
+            params.join(", "),
+            self.print_body(&function.body)
+        ));
+        out
+    }
+
+    fn print_body(&mut self, statements: &[StmtId]) -> String {
+        let mut out = String::from("{");
+        for &statement in statements {
+            out.push(' ');
+            out.push_str(&self.print_stmt(statement));
+        }
+        out.push_str(" }");
+        out
+    }
+}
+
+impl ExprVisitor<String> for RpnPrinter<'_> {
+    fn visit_assign_expr(&mut self, _id: ExprId, name: &Token, value: ExprId) -> String {
+        format!("{} {} =", name.lexeme, self.print(value))
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        self.postfix(&[left, right], operator.lexeme.as_str())
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        _id: ExprId,
+        callee: ExprId,
+        _paren: &Token,
+        arguments: &[ExprId],
+    ) -> String {
+        let mut operands = vec![callee];
+        operands.extend_from_slice(arguments);
+        self.postfix(&operands, &format!("call/{}", arguments.len()))
+    }
+
+    fn visit_comma_expr(&mut self, _id: ExprId, left: ExprId, right: ExprId) -> String {
+        self.postfix(&[left, right], ",")
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        _id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> String {
+        self.postfix(&[condition, then_branch, else_branch], "?:")
+    }
+
+    fn visit_function_expr(&mut self, _id: ExprId, function: &Rc<Function>) -> String {
+        format!("fun{}", self.print_function(function))
+    }
+
+    fn visit_get_expr(&mut self, _id: ExprId, object: ExprId, name: &Token) -> String {
+        format!("{} .{}", self.print(object), name.lexeme)
+    }
+
+    fn visit_grouping_expr(&mut self, _id: ExprId, expression: ExprId) -> String {
+        self.print(expression)
+    }
+
+    fn visit_literal_expr(&mut self, _id: ExprId, value: &Token) -> String {
+        match &value.literal {
+            // Quoted, so a string with spaces still reads as one operand.
+            Some(Literal::String(s)) => format!("{:?}", s.as_str()),
+            Some(literal) => literal.to_string(),
+            None => value.lexeme.to_string(),
+        }
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> String {
+        self.postfix(&[left, right], operator.lexeme.as_str())
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        _id: ExprId,
+        object: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> String {
+        format!(
+            "{} .{} {} =",
+            self.print(object),
+            name.lexeme,
+            self.print(value)
+        )
+    }
+
+    fn visit_super_expr(&mut self, _id: ExprId, _keyword: &Token, method: &Token) -> String {
+        format!("super.{}", method.lexeme)
+    }
+
+    fn visit_this_expr(&mut self, _id: ExprId, _keyword: &Token) -> String {
+        "this".to_string()
+    }
+
+    fn visit_unary_expr(&mut self, _id: ExprId, operator: &Token, right: ExprId) -> String {
+        // A bare `-` would read as subtraction.
+        let operator = match operator.token_type {
+            TokenType::Minus => "neg",
+            _ => operator.lexeme.as_str(),
+        };
+        self.postfix(&[right], operator)
+    }
+
+    fn visit_variable_expr(&mut self, _id: ExprId, name: &Token) -> String {
+        name.lexeme.to_string()
+    }
+
+    fn visit_interpolation_expr(&mut self, _id: ExprId, parts: &[ExprId]) -> String {
+        self.postfix(parts, &format!("interpolate/{}", parts.len()))
+    }
+}
+
+impl StmtVisitor<String> for RpnPrinter<'_> {
+    fn visit_block_stmt(&mut self, _id: StmtId, statements: &[StmtId]) -> String {
+        self.print_body(statements)
+    }
+
+    fn visit_break_stmt(&mut self, _id: StmtId, _keyword: &Token) -> String {
+        "break;".to_string()
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        _id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> String {
+        let mut out = format!("class {}", name.lexeme);
+        if let Some(superclass) = superclass {
+            out.push_str(&format!(" < {}", self.print(superclass)));
+        }
+        out.push_str(" {");
+        for method in methods {
+            out.push(' ');
+            out.push_str(&self.print_function(method));
+        }
+        out.push_str(" }");
+        out
+    }
+
+    fn visit_continue_stmt(&mut self, _id: StmtId, _keyword: &Token) -> String {
+        "continue;".to_string()
+    }
+
+    fn visit_expression_stmt(&mut self, _id: StmtId, expression: ExprId) -> String {
+        format!("{};", self.print(expression))
+    }
+
+    fn visit_function_stmt(&mut self, _id: StmtId, function: &Rc<Function>) -> String {
+        format!("fun {}", self.print_function(function))
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> String {
+        let mut out = format!(
+            "if ({}) {}",
+            self.print(condition),
+            self.print_stmt(then_branch)
+        );
+        if let Some(else_branch) = else_branch {
+            out.push_str(&format!(" else {}", self.print_stmt(else_branch)));
+        }
+        out
+    }
+
+    fn visit_print_stmt(&mut self, _id: StmtId, expression: ExprId) -> String {
+        format!("print {};", self.print(expression))
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _id: StmtId,
+        _keyword: &Token,
+        value: Option<ExprId>,
+    ) -> String {
+        match value {
+            Some(value) => format!("return {};", self.print(value)),
+            None => "return;".to_string(),
+        }
+    }
+
+    fn visit_var_stmt(&mut self, _id: StmtId, name: &Token, initializer: Option<ExprId>) -> String {
+        match initializer {
+            Some(initializer) => format!("var {} = {};", name.lexeme, self.print(initializer)),
+            None => format!("var {};", name.lexeme),
+        }
+    }
+
+    // A `while` with an increment came from a `for` loop, and prints as one.
+    fn visit_while_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> String {
+        match increment {
+            Some(increment) => format!(
+                "for (; {}; {}) {}",
+                self.print(condition),
+                self.print(increment),
+                self.print_stmt(body)
+            ),
+            None => format!(
+                "while ({}) {}",
+                self.print(condition),
+                self.print_stmt(body)
+            ),
+        }
+    }
+}
@@ -0,0 +1,1280 @@
+use crate::ast::arena::Ast;
+use crate::errors::deserialize_error::DeserializeError;
+use crate::expression::expression::{Expr, ExprId, ExprVisitor};
+use crate::scanner::scanner;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::{Literal, Span, Token, TokenType};
+use crate::statement::statement::{Function, Stmt, StmtId, StmtVisitor};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+/// Version of the document `to_json` writes. Bump it whenever a change would
+/// make older documents mean something else, or fail to load.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serializes a parsed program as `{"version":1,"statements":[...]}`, the
+/// document `--emit-ast=json` prints.
+///
+/// Every node is an object with its `kind` and `span`, plus one member per
+/// field: literals as JSON values, children as nested nodes, and names and
+/// operators as `{"lexeme":..,"span":..}` objects, so a loaded tree reports
+/// errors where the original would.
+pub fn to_json(ast: &Ast, statements: &[StmtId]) -> String {
+    let mut writer = Writer { ast };
+    let program = Program {
+        version: SCHEMA_VERSION,
+        statements: statements.iter().map(|&stmt| writer.stmt(stmt)).collect(),
+    };
+    serde_json::to_string_pretty(&program).expect("AST serializes to JSON")
+}
+
+/// Loads a document written by `to_json`, or by another tool following the
+/// same schema, into `ast` and returns its top-level statements.
+///
+/// Spans may be left out, and default to zero. Only the tree's shape and its
+/// names are checked here; the resolver still has to accept it before it
+/// runs. A document nested deeper than a parser limited to `max_depth` and
+/// `max_height` could have produced is rejected, so whatever that parser
+/// accepts also loads.
+pub fn from_json(
+    json: &str,
+    ast: &Ast,
+    max_depth: usize,
+    max_height: usize,
+) -> Result<Vec<StmtId>, DeserializeError> {
+    let deepest = check_depth(json, max_depth, max_height)?;
+
+    // serde_json takes far more stack per level than the passes over the
+    // arena do, so the document is read on a thread with room for how deeply
+    // it actually nests.
+    let stack_size = STACK_PER_LEVEL
+        .saturating_mul(deepest)
+        .saturating_add(MIN_STACK_SIZE);
+    let program = std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn_scoped(scope, || read_program(json))
+            .map_err(|_| DeserializeError::new("AST nesting too deep."))?
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })?;
+
+    let reader = Reader { ast };
+    program
+        .statements
+        .into_iter()
+        .map(|stmt| reader.stmt(stmt))
+        .collect()
+}
+
+/// Stack a debug build needs to read one level of a document, with room to
+/// spare.
+const STACK_PER_LEVEL: usize = 16 * 1024;
+const MIN_STACK_SIZE: usize = 256 * 1024;
+
+fn read_program(json: &str) -> Result<Program, DeserializeError> {
+    // Check the version first, so a newer document fails with a clear message
+    // rather than whatever its first unknown field happens to cause.
+    let Header { version } = parse(json)?;
+    if version != SCHEMA_VERSION {
+        return Err(DeserializeError::new(format!(
+            "Unsupported AST schema version {}, expected {}.",
+            version, SCHEMA_VERSION
+        )));
+    }
+    Ok(parse(json)?)
+}
+
+// serde_json stops at 128 levels, well short of what the parser accepts, so
+// its limit is lifted and `check_depth` bounds the recursion instead.
+fn parse<'de, T: Deserialize<'de>>(json: &'de str) -> Result<T, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+// Rejects documents nested deeper than a program parsed with `max_depth` and
+// `max_height` can be, before serde_json recurses into them, and returns how
+// deeply the document nests. Methods nest deepest per level of depth: a class, its `methods`, the method and its
+// `body`, two of them objects. Each level of an expression's height is at
+// most a node and the `arguments` of a call. Around that come the program,
+// its `statements`, and a name with its span.
+//
+// Objects are held to the tighter limit since every node is one, and a node
+// nested directly in another takes the most stack to load.
+fn check_depth(json: &str, max_depth: usize, max_height: usize) -> Result<usize, DeserializeError> {
+    let max_values = max_depth
+        .saturating_mul(4)
+        .saturating_add(max_height.saturating_mul(2))
+        .saturating_add(4);
+    let max_objects = max_depth
+        .saturating_mul(2)
+        .saturating_add(max_height)
+        .saturating_add(4);
+    let (mut values, mut objects, mut deepest) = (0usize, 0usize, 0usize);
+    let mut in_string = false;
+    let mut escaped = false;
+    for byte in json.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' => values += 1,
+            b'{' => {
+                values += 1;
+                objects += 1;
+            }
+            // Unbalanced brackets are left for serde_json to report.
+            b']' => values = values.saturating_sub(1),
+            b'}' => {
+                values = values.saturating_sub(1);
+                objects = objects.saturating_sub(1);
+            }
+            _ => continue,
+        }
+        if values > max_values || objects > max_objects {
+            return Err(DeserializeError::new("AST nesting too deep."));
+        }
+        deepest = deepest.max(values);
+    }
+    Ok(deepest)
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Program {
+    version: u32,
+    statements: Vec<StmtJson>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+struct SpanJson {
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+impl From<Span> for SpanJson {
+    fn from(span: Span) -> Self {
+        SpanJson {
+            start: span.start,
+            end: span.end,
+            line: span.line,
+            column: span.column,
+        }
+    }
+}
+
+impl From<SpanJson> for Span {
+    fn from(span: SpanJson) -> Self {
+        Span::new(span.start, span.end, span.line, span.column)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenJson {
+    lexeme: String,
+    #[serde(default)]
+    span: SpanJson,
+}
+
+/// `null` is `nil`. JSON has no infinities or NaN, so those numbers are
+/// written as `{"number":"inf"}`, `{"number":"-inf"}` or `{"number":"nan"}`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum LiteralJson {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    NonFinite { number: NonFinite },
+}
+
+#[derive(Serialize, Deserialize)]
+enum NonFinite {
+    #[serde(rename = "inf")]
+    Infinity,
+    #[serde(rename = "-inf")]
+    NegativeInfinity,
+    #[serde(rename = "nan")]
+    NaN,
+}
+
+impl From<f64> for LiteralJson {
+    fn from(n: f64) -> Self {
+        if n.is_finite() {
+            return LiteralJson::Number(n);
+        }
+        let number = if n.is_nan() {
+            NonFinite::NaN
+        } else if n > 0.0 {
+            NonFinite::Infinity
+        } else {
+            NonFinite::NegativeInfinity
+        };
+        LiteralJson::NonFinite { number }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum ExprJson {
+    Assign {
+        #[serde(default)]
+        span: SpanJson,
+        name: TokenJson,
+        value: Box<ExprJson>,
+    },
+    Binary {
+        #[serde(default)]
+        span: SpanJson,
+        operator: TokenJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    },
+    /// `paren` is where the closing parenthesis was, for runtime errors.
+    Call {
+        #[serde(default)]
+        span: SpanJson,
+        callee: Box<ExprJson>,
+        #[serde(default)]
+        paren: SpanJson,
+        arguments: Vec<ExprJson>,
+    },
+    Comma {
+        #[serde(default)]
+        span: SpanJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    },
+    Conditional {
+        #[serde(default)]
+        span: SpanJson,
+        condition: Box<ExprJson>,
+        then_branch: Box<ExprJson>,
+        else_branch: Box<ExprJson>,
+    },
+    Function {
+        #[serde(default)]
+        span: SpanJson,
+        name: Option<TokenJson>,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    },
+    Get {
+        #[serde(default)]
+        span: SpanJson,
+        object: Box<ExprJson>,
+        name: TokenJson,
+    },
+    Grouping {
+        #[serde(default)]
+        span: SpanJson,
+        expression: Box<ExprJson>,
+    },
+    Literal {
+        #[serde(default)]
+        span: SpanJson,
+        value: Option<LiteralJson>,
+    },
+    Logical {
+        #[serde(default)]
+        span: SpanJson,
+        operator: TokenJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    },
+    Set {
+        #[serde(default)]
+        span: SpanJson,
+        object: Box<ExprJson>,
+        name: TokenJson,
+        value: Box<ExprJson>,
+    },
+    Super {
+        #[serde(default)]
+        span: SpanJson,
+        method: TokenJson,
+    },
+    This {
+        #[serde(default)]
+        span: SpanJson,
+    },
+    Unary {
+        #[serde(default)]
+        span: SpanJson,
+        operator: TokenJson,
+        right: Box<ExprJson>,
+    },
+    Variable {
+        #[serde(default)]
+        span: SpanJson,
+        name: TokenJson,
+    },
+    Interpolation {
+        #[serde(default)]
+        span: SpanJson,
+        parts: Vec<ExprJson>,
+    },
+}
+
+// Keywords (`this`, `return`, ...) aren't stored: they always start their
+// node, so the node's span stands in for theirs.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum StmtJson {
+    Block {
+        #[serde(default)]
+        span: SpanJson,
+        statements: Vec<StmtJson>,
+    },
+    Break {
+        #[serde(default)]
+        span: SpanJson,
+    },
+    Class {
+        #[serde(default)]
+        span: SpanJson,
+        name: TokenJson,
+        superclass: Option<ExprJson>,
+        methods: Vec<MethodJson>,
+    },
+    Continue {
+        #[serde(default)]
+        span: SpanJson,
+    },
+    Expression {
+        #[serde(default)]
+        span: SpanJson,
+        expression: ExprJson,
+    },
+    Function {
+        #[serde(default)]
+        span: SpanJson,
+        name: TokenJson,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    },
+    If {
+        #[serde(default)]
+        span: SpanJson,
+        condition: ExprJson,
+        then_branch: Box<StmtJson>,
+        else_branch: Option<Box<StmtJson>>,
+    },
+    Print {
+        #[serde(default)]
+        span: SpanJson,
+        expression: ExprJson,
+    },
+    Return {
+        #[serde(default)]
+        span: SpanJson,
+        value: Option<ExprJson>,
+    },
+    Var {
+        #[serde(default)]
+        span: SpanJson,
+        name: TokenJson,
+        initializer: Option<ExprJson>,
+    },
+    While {
+        #[serde(default)]
+        span: SpanJson,
+        condition: ExprJson,
+        body: Box<StmtJson>,
+        increment: Option<ExprJson>,
+    },
+}
+
+/// A method isn't a node of its own, so unlike a function it has no span.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum MethodJson {
+    Function {
+        name: TokenJson,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    },
+}
+
+struct Writer<'a> {
+    ast: &'a Ast,
+}
+
+impl Writer<'_> {
+    fn expr(&mut self, expr: ExprId) -> Box<ExprJson> {
+        Box::new(expr.accept(self.ast, self))
+    }
+
+    fn stmt(&mut self, stmt: StmtId) -> StmtJson {
+        stmt.accept(self.ast, self)
+    }
+
+    fn exprs(&mut self, exprs: &[ExprId]) -> Vec<ExprJson> {
+        exprs.iter().map(|&expr| *self.expr(expr)).collect()
+    }
+
+    fn stmts(&mut self, stmts: &[StmtId]) -> Vec<StmtJson> {
+        stmts.iter().map(|&stmt| self.stmt(stmt)).collect()
+    }
+
+    fn optional(&mut self, expr: Option<ExprId>) -> Option<ExprJson> {
+        expr.map(|expr| *self.expr(expr))
+    }
+
+    fn expr_span(&self, id: ExprId) -> SpanJson {
+        self.ast.expr_span(id).into()
+    }
+
+    fn stmt_span(&self, id: StmtId) -> SpanJson {
+        self.ast.stmt_span(id).into()
+    }
+}
+
+fn token(token: &Token) -> TokenJson {
+    TokenJson {
+        lexeme: token.lexeme.as_str().to_string(),
+        span: token.span.into(),
+    }
+}
+
+fn tokens(tokens: &[Token]) -> Vec<TokenJson> {
+    tokens.iter().map(token).collect()
+}
+
+impl ExprVisitor<ExprJson> for Writer<'_> {
+    fn visit_assign_expr(&mut self, id: ExprId, name: &Token, value: ExprId) -> ExprJson {
+        ExprJson::Assign {
+            span: self.expr_span(id),
+            name: token(name),
+            value: self.expr(value),
+        }
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> ExprJson {
+        ExprJson::Binary {
+            span: self.expr_span(id),
+            operator: token(operator),
+            left: self.expr(left),
+            right: self.expr(right),
+        }
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        id: ExprId,
+        callee: ExprId,
+        paren: &Token,
+        arguments: &[ExprId],
+    ) -> ExprJson {
+        ExprJson::Call {
+            span: self.expr_span(id),
+            callee: self.expr(callee),
+            paren: paren.span.into(),
+            arguments: self.exprs(arguments),
+        }
+    }
+
+    fn visit_comma_expr(&mut self, id: ExprId, left: ExprId, right: ExprId) -> ExprJson {
+        ExprJson::Comma {
+            span: self.expr_span(id),
+            left: self.expr(left),
+            right: self.expr(right),
+        }
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> ExprJson {
+        ExprJson::Conditional {
+            span: self.expr_span(id),
+            condition: self.expr(condition),
+            then_branch: self.expr(then_branch),
+            else_branch: self.expr(else_branch),
+        }
+    }
+
+    fn visit_function_expr(&mut self, id: ExprId, function: &Rc<Function>) -> ExprJson {
+        ExprJson::Function {
+            span: self.expr_span(id),
+            name: function.name.as_ref().map(token),
+            params: tokens(&function.params),
+            body: self.stmts(&function.body),
+        }
+    }
+
+    fn visit_get_expr(&mut self, id: ExprId, object: ExprId, name: &Token) -> ExprJson {
+        ExprJson::Get {
+            span: self.expr_span(id),
+            object: self.expr(object),
+            name: token(name),
+        }
+    }
+
+    fn visit_grouping_expr(&mut self, id: ExprId, expression: ExprId) -> ExprJson {
+        ExprJson::Grouping {
+            span: self.expr_span(id),
+            expression: self.expr(expression),
+        }
+    }
+
+    fn visit_literal_expr(&mut self, id: ExprId, value: &Token) -> ExprJson {
+        let value = match &value.literal {
+            Some(Literal::Number(n)) => Some(LiteralJson::from(*n)),
+            Some(Literal::String(s)) => Some(LiteralJson::String(s.as_str().to_string())),
+            Some(Literal::Bool(b)) => Some(LiteralJson::Bool(*b)),
+            Some(Literal::Nil) | None => None,
+        };
+        ExprJson::Literal {
+            span: self.expr_span(id),
+            value,
+        }
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> ExprJson {
+        ExprJson::Logical {
+            span: self.expr_span(id),
+            operator: token(operator),
+            left: self.expr(left),
+            right: self.expr(right),
+        }
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        id: ExprId,
+        object: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> ExprJson {
+        ExprJson::Set {
+            span: self.expr_span(id),
+            object: self.expr(object),
+            name: token(name),
+            value: self.expr(value),
+        }
+    }
+
+    fn visit_super_expr(&mut self, id: ExprId, _keyword: &Token, method: &Token) -> ExprJson {
+        ExprJson::Super {
+            span: self.expr_span(id),
+            method: token(method),
+        }
+    }
+
+    fn visit_this_expr(&mut self, id: ExprId, _keyword: &Token) -> ExprJson {
+        ExprJson::This {
+            span: self.expr_span(id),
+        }
+    }
+
+    fn visit_unary_expr(&mut self, id: ExprId, operator: &Token, right: ExprId) -> ExprJson {
+        ExprJson::Unary {
+            span: self.expr_span(id),
+            operator: token(operator),
+            right: self.expr(right),
+        }
+    }
+
+    fn visit_variable_expr(&mut self, id: ExprId, name: &Token) -> ExprJson {
+        ExprJson::Variable {
+            span: self.expr_span(id),
+            name: token(name),
+        }
+    }
+
+    fn visit_interpolation_expr(&mut self, id: ExprId, parts: &[ExprId]) -> ExprJson {
+        ExprJson::Interpolation {
+            span: self.expr_span(id),
+            parts: self.exprs(parts),
+        }
+    }
+}
+
+impl StmtVisitor<StmtJson> for Writer<'_> {
+    fn visit_block_stmt(&mut self, id: StmtId, statements: &[StmtId]) -> StmtJson {
+        StmtJson::Block {
+            span: self.stmt_span(id),
+            statements: self.stmts(statements),
+        }
+    }
+
+    fn visit_break_stmt(&mut self, id: StmtId, _keyword: &Token) -> StmtJson {
+        StmtJson::Break {
+            span: self.stmt_span(id),
+        }
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> StmtJson {
+        let methods = methods
+            .iter()
+            .map(|method| MethodJson::Function {
+                name: token(method.name.as_ref().expect("methods have names")),
+                params: tokens(&method.params),
+                body: self.stmts(&method.body),
+            })
+            .collect();
+        StmtJson::Class {
+            span: self.stmt_span(id),
+            name: token(name),
+            superclass: self.optional(superclass),
+            methods,
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, id: StmtId, _keyword: &Token) -> StmtJson {
+        StmtJson::Continue {
+            span: self.stmt_span(id),
+        }
+    }
+
+    fn visit_expression_stmt(&mut self, id: StmtId, expression: ExprId) -> StmtJson {
+        StmtJson::Expression {
+            span: self.stmt_span(id),
+            expression: *self.expr(expression),
+        }
+    }
+
+    fn visit_function_stmt(&mut self, id: StmtId, function: &Rc<Function>) -> StmtJson {
+        StmtJson::Function {
+            span: self.stmt_span(id),
+            name: token(function.name.as_ref().expect("declarations have names")),
+            params: tokens(&function.params),
+            body: self.stmts(&function.body),
+        }
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> StmtJson {
+        StmtJson::If {
+            span: self.stmt_span(id),
+            condition: *self.expr(condition),
+            then_branch: Box::new(self.stmt(then_branch)),
+            else_branch: else_branch.map(|stmt| Box::new(self.stmt(stmt))),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, id: StmtId, expression: ExprId) -> StmtJson {
+        StmtJson::Print {
+            span: self.stmt_span(id),
+            expression: *self.expr(expression),
+        }
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        id: StmtId,
+        _keyword: &Token,
+        value: Option<ExprId>,
+    ) -> StmtJson {
+        StmtJson::Return {
+            span: self.stmt_span(id),
+            value: self.optional(value),
+        }
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        id: StmtId,
+        name: &Token,
+        initializer: Option<ExprId>,
+    ) -> StmtJson {
+        StmtJson::Var {
+            span: self.stmt_span(id),
+            name: token(name),
+            initializer: self.optional(initializer),
+        }
+    }
+
+    fn visit_while_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> StmtJson {
+        StmtJson::While {
+            span: self.stmt_span(id),
+            condition: *self.expr(condition),
+            body: Box::new(self.stmt(body)),
+            increment: self.optional(increment),
+        }
+    }
+}
+
+// Adds nodes children first, the same order the parser does.
+//
+// Each kind is built by a method of its own rather than in one large match,
+// so the frames a deeply nested document stacks up stay small even in debug
+// builds, where the locals of every arm would share one frame. Children are
+// passed on still boxed for the same reason.
+struct Reader<'a> {
+    ast: &'a Ast,
+}
+
+#[allow(clippy::boxed_local)]
+impl Reader<'_> {
+    fn expr(&self, expr: ExprJson) -> Result<ExprId, DeserializeError> {
+        match expr {
+            ExprJson::Assign { span, name, value } => self.assign(span, name, value),
+            ExprJson::Binary {
+                span,
+                operator,
+                left,
+                right,
+            } => self.binary(span, operator, left, right),
+            ExprJson::Call {
+                span,
+                callee,
+                paren,
+                arguments,
+            } => self.call(span, callee, paren, arguments),
+            ExprJson::Comma { span, left, right } => self.comma(span, left, right),
+            ExprJson::Conditional {
+                span,
+                condition,
+                then_branch,
+                else_branch,
+            } => self.conditional(span, condition, then_branch, else_branch),
+            ExprJson::Function {
+                span,
+                name,
+                params,
+                body,
+            } => self.lambda(span, name, params, body),
+            ExprJson::Get { span, object, name } => self.get(span, object, name),
+            ExprJson::Grouping { span, expression } => self.grouping(span, expression),
+            ExprJson::Literal { span, value } => self.literal(span, value),
+            ExprJson::Logical {
+                span,
+                operator,
+                left,
+                right,
+            } => self.logical(span, operator, left, right),
+            ExprJson::Set {
+                span,
+                object,
+                name,
+                value,
+            } => self.set(span, object, name, value),
+            ExprJson::Super { span, method } => self.super_(span, method),
+            ExprJson::This { span } => self.this(span),
+            ExprJson::Unary {
+                span,
+                operator,
+                right,
+            } => self.unary(span, operator, right),
+            ExprJson::Variable { span, name } => self.variable(span, name),
+            ExprJson::Interpolation { span, parts } => self.interpolation(span, parts),
+        }
+    }
+
+    fn add_expr(&self, expr: Expr, span: SpanJson) -> Result<ExprId, DeserializeError> {
+        Ok(self.ast.add_expr(expr, span.into()))
+    }
+
+    fn assign(
+        &self,
+        span: SpanJson,
+        name: TokenJson,
+        value: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let value = self.expr(*value)?;
+        let name = identifier(name)?;
+        self.add_expr(Expr::Assign { name, value }, span)
+    }
+
+    fn binary(
+        &self,
+        span: SpanJson,
+        operator: TokenJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let operator = operator_token(operator, "binary", BINARY_OPERATORS)?;
+        let left = self.expr(*left)?;
+        let right = self.expr(*right)?;
+        let expr = Expr::Binary {
+            left,
+            operator,
+            right,
+        };
+        self.add_expr(expr, span)
+    }
+
+    fn call(
+        &self,
+        span: SpanJson,
+        callee: Box<ExprJson>,
+        paren: SpanJson,
+        arguments: Vec<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let callee = self.expr(*callee)?;
+        let paren = keyword(TokenType::RightParen, ")", paren);
+        let arguments = self.exprs(arguments)?;
+        let expr = Expr::Call {
+            callee,
+            paren,
+            arguments,
+        };
+        self.add_expr(expr, span)
+    }
+
+    fn comma(
+        &self,
+        span: SpanJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let left = self.expr(*left)?;
+        let right = self.expr(*right)?;
+        self.add_expr(Expr::Comma { left, right }, span)
+    }
+
+    fn conditional(
+        &self,
+        span: SpanJson,
+        condition: Box<ExprJson>,
+        then_branch: Box<ExprJson>,
+        else_branch: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let expr = Expr::Conditional {
+            condition: self.expr(*condition)?,
+            then_branch: self.expr(*then_branch)?,
+            else_branch: self.expr(*else_branch)?,
+        };
+        self.add_expr(expr, span)
+    }
+
+    fn lambda(
+        &self,
+        span: SpanJson,
+        name: Option<TokenJson>,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let function = self.function(name, params, body)?;
+        self.add_expr(Expr::Function(function), span)
+    }
+
+    fn get(
+        &self,
+        span: SpanJson,
+        object: Box<ExprJson>,
+        name: TokenJson,
+    ) -> Result<ExprId, DeserializeError> {
+        let object = self.expr(*object)?;
+        let name = identifier(name)?;
+        self.add_expr(Expr::Get { object, name }, span)
+    }
+
+    fn grouping(
+        &self,
+        span: SpanJson,
+        expression: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let expression = self.expr(*expression)?;
+        self.add_expr(Expr::Grouping { expression }, span)
+    }
+
+    fn literal(
+        &self,
+        span: SpanJson,
+        value: Option<LiteralJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let value = literal(value, span);
+        self.add_expr(Expr::Literal { value }, span)
+    }
+
+    fn logical(
+        &self,
+        span: SpanJson,
+        operator: TokenJson,
+        left: Box<ExprJson>,
+        right: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let operator = operator_token(operator, "logical", LOGICAL_OPERATORS)?;
+        let left = self.expr(*left)?;
+        let right = self.expr(*right)?;
+        let expr = Expr::Logical {
+            left,
+            operator,
+            right,
+        };
+        self.add_expr(expr, span)
+    }
+
+    fn set(
+        &self,
+        span: SpanJson,
+        object: Box<ExprJson>,
+        name: TokenJson,
+        value: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let object = self.expr(*object)?;
+        let name = identifier(name)?;
+        let value = self.expr(*value)?;
+        let expr = Expr::Set {
+            object,
+            name,
+            value,
+        };
+        self.add_expr(expr, span)
+    }
+
+    fn super_(&self, span: SpanJson, method: TokenJson) -> Result<ExprId, DeserializeError> {
+        let keyword = keyword(TokenType::Super, "super", span);
+        let method = identifier(method)?;
+        self.add_expr(Expr::Super { keyword, method }, span)
+    }
+
+    fn this(&self, span: SpanJson) -> Result<ExprId, DeserializeError> {
+        let keyword = keyword(TokenType::This, "this", span);
+        self.add_expr(Expr::This { keyword }, span)
+    }
+
+    fn unary(
+        &self,
+        span: SpanJson,
+        operator: TokenJson,
+        right: Box<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let operator = operator_token(operator, "unary", UNARY_OPERATORS)?;
+        let right = self.expr(*right)?;
+        self.add_expr(Expr::Unary { operator, right }, span)
+    }
+
+    fn variable(&self, span: SpanJson, name: TokenJson) -> Result<ExprId, DeserializeError> {
+        let name = identifier(name)?;
+        self.add_expr(Expr::Variable { name }, span)
+    }
+
+    fn interpolation(
+        &self,
+        span: SpanJson,
+        parts: Vec<ExprJson>,
+    ) -> Result<ExprId, DeserializeError> {
+        let parts = self.exprs(parts)?;
+        self.add_expr(Expr::Interpolation { parts }, span)
+    }
+
+    fn stmt(&self, stmt: StmtJson) -> Result<StmtId, DeserializeError> {
+        match stmt {
+            StmtJson::Block { span, statements } => self.block(span, statements),
+            StmtJson::Break { span } => {
+                let keyword = keyword(TokenType::Break, "break", span);
+                self.add_stmt(Stmt::Break { keyword }, span)
+            }
+            StmtJson::Class {
+                span,
+                name,
+                superclass,
+                methods,
+            } => self.class(span, name, superclass, methods),
+            StmtJson::Continue { span } => {
+                let keyword = keyword(TokenType::Continue, "continue", span);
+                self.add_stmt(Stmt::Continue { keyword }, span)
+            }
+            StmtJson::Expression { span, expression } => self.expression(span, expression),
+            StmtJson::Function {
+                span,
+                name,
+                params,
+                body,
+            } => self.function_declaration(span, name, params, body),
+            StmtJson::If {
+                span,
+                condition,
+                then_branch,
+                else_branch,
+            } => self.if_(span, condition, then_branch, else_branch),
+            StmtJson::Print { span, expression } => self.print(span, expression),
+            StmtJson::Return { span, value } => self.return_(span, value),
+            StmtJson::Var {
+                span,
+                name,
+                initializer,
+            } => self.var(span, name, initializer),
+            StmtJson::While {
+                span,
+                condition,
+                body,
+                increment,
+            } => self.while_(span, condition, body, increment),
+        }
+    }
+
+    fn add_stmt(&self, stmt: Stmt, span: SpanJson) -> Result<StmtId, DeserializeError> {
+        Ok(self.ast.add_stmt(stmt, span.into()))
+    }
+
+    fn block(&self, span: SpanJson, statements: Vec<StmtJson>) -> Result<StmtId, DeserializeError> {
+        let statements = self.stmts(statements)?;
+        self.add_stmt(Stmt::Block { statements }, span)
+    }
+
+    fn class(
+        &self,
+        span: SpanJson,
+        name: TokenJson,
+        superclass: Option<ExprJson>,
+        methods: Vec<MethodJson>,
+    ) -> Result<StmtId, DeserializeError> {
+        let name = identifier(name)?;
+        let superclass = match superclass {
+            Some(superclass @ ExprJson::Variable { .. }) => Some(self.expr(superclass)?),
+            Some(_) => {
+                return Err(DeserializeError::new(format!(
+                    "Superclass of '{}' must be a Variable.",
+                    name.lexeme
+                )))
+            }
+            None => None,
+        };
+        let methods = methods
+            .into_iter()
+            .map(|MethodJson::Function { name, params, body }| {
+                self.function(Some(name), params, body)
+            })
+            .collect::<Result<_, _>>()?;
+        let stmt = Stmt::Class {
+            name,
+            superclass,
+            methods,
+        };
+        self.add_stmt(stmt, span)
+    }
+
+    fn expression(&self, span: SpanJson, expression: ExprJson) -> Result<StmtId, DeserializeError> {
+        let expression = self.expr(expression)?;
+        self.add_stmt(Stmt::Expression { expression }, span)
+    }
+
+    fn function_declaration(
+        &self,
+        span: SpanJson,
+        name: TokenJson,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    ) -> Result<StmtId, DeserializeError> {
+        let function = self.function(Some(name), params, body)?;
+        self.add_stmt(Stmt::Function(function), span)
+    }
+
+    fn if_(
+        &self,
+        span: SpanJson,
+        condition: ExprJson,
+        then_branch: Box<StmtJson>,
+        else_branch: Option<Box<StmtJson>>,
+    ) -> Result<StmtId, DeserializeError> {
+        let stmt = Stmt::If {
+            condition: self.expr(condition)?,
+            then_branch: self.stmt(*then_branch)?,
+            else_branch: else_branch.map(|stmt| self.stmt(*stmt)).transpose()?,
+        };
+        self.add_stmt(stmt, span)
+    }
+
+    fn print(&self, span: SpanJson, expression: ExprJson) -> Result<StmtId, DeserializeError> {
+        let expression = self.expr(expression)?;
+        self.add_stmt(Stmt::Print { expression }, span)
+    }
+
+    fn return_(&self, span: SpanJson, value: Option<ExprJson>) -> Result<StmtId, DeserializeError> {
+        let keyword = keyword(TokenType::Return, "return", span);
+        let value = self.optional(value)?;
+        self.add_stmt(Stmt::Return { keyword, value }, span)
+    }
+
+    fn var(
+        &self,
+        span: SpanJson,
+        name: TokenJson,
+        initializer: Option<ExprJson>,
+    ) -> Result<StmtId, DeserializeError> {
+        let name = identifier(name)?;
+        let initializer = self.optional(initializer)?;
+        self.add_stmt(Stmt::Var { name, initializer }, span)
+    }
+
+    fn while_(
+        &self,
+        span: SpanJson,
+        condition: ExprJson,
+        body: Box<StmtJson>,
+        increment: Option<ExprJson>,
+    ) -> Result<StmtId, DeserializeError> {
+        let stmt = Stmt::While {
+            condition: self.expr(condition)?,
+            body: self.stmt(*body)?,
+            increment: self.optional(increment)?,
+        };
+        self.add_stmt(stmt, span)
+    }
+
+    fn exprs(&self, exprs: Vec<ExprJson>) -> Result<Vec<ExprId>, DeserializeError> {
+        exprs.into_iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    fn stmts(&self, stmts: Vec<StmtJson>) -> Result<Vec<StmtId>, DeserializeError> {
+        stmts.into_iter().map(|stmt| self.stmt(stmt)).collect()
+    }
+
+    fn optional(&self, expr: Option<ExprJson>) -> Result<Option<ExprId>, DeserializeError> {
+        expr.map(|expr| self.expr(expr)).transpose()
+    }
+
+    fn function(
+        &self,
+        name: Option<TokenJson>,
+        params: Vec<TokenJson>,
+        body: Vec<StmtJson>,
+    ) -> Result<Rc<Function>, DeserializeError> {
+        Ok(Rc::new(Function {
+            name: name.map(identifier).transpose()?,
+            params: params
+                .into_iter()
+                .map(identifier)
+                .collect::<Result<_, _>>()?,
+            body: self.stmts(body)?,
+        }))
+    }
+}
+
+const BINARY_OPERATORS: &[(&str, TokenType)] = &[
+    ("+", TokenType::Plus),
+    ("-", TokenType::Minus),
+    ("*", TokenType::Star),
+    ("/", TokenType::Slash),
+    (">", TokenType::Greater),
+    (">=", TokenType::GreaterEqual),
+    ("<", TokenType::Less),
+    ("<=", TokenType::LessEqual),
+    ("==", TokenType::EqualEqual),
+    ("!=", TokenType::BangEqual),
+];
+
+const LOGICAL_OPERATORS: &[(&str, TokenType)] = &[("and", TokenType::And), ("or", TokenType::Or)];
+
+const UNARY_OPERATORS: &[(&str, TokenType)] = &[("!", TokenType::Bang), ("-", TokenType::Minus)];
+
+// The interpreter trusts the parser to only build operators it knows, so an
+// unknown one has to be caught here instead.
+fn operator_token(
+    operator: TokenJson,
+    kind: &str,
+    operators: &[(&str, TokenType)],
+) -> Result<Token, DeserializeError> {
+    match operators
+        .iter()
+        .find(|(lexeme, _)| *lexeme == operator.lexeme)
+    {
+        Some(&(_, token_type)) => Ok(token_from(operator, token_type)),
+        None => Err(DeserializeError::new(format!(
+            "Unknown {} operator '{}'.",
+            kind, operator.lexeme
+        ))),
+    }
+}
+
+// The resolver and interpreter find `this` and `super` by name, so a variable
+// spelled like either could stand in for them. Only names the scanner could
+// have produced are let through.
+fn identifier(name: TokenJson) -> Result<Token, DeserializeError> {
+    if scanner::keyword(&name.lexeme).is_some() {
+        return Err(DeserializeError::new(format!(
+            "Reserved word '{}' can't be used as a name.",
+            name.lexeme
+        )));
+    }
+    if !scanner::is_identifier(&name.lexeme) {
+        return Err(DeserializeError::new(format!(
+            "'{}' is not a valid name.",
+            name.lexeme
+        )));
+    }
+    Ok(token_from(name, TokenType::Identifier))
+}
+
+fn token_from(token: TokenJson, token_type: TokenType) -> Token {
+    Token::new(
+        token_type,
+        Symbol::intern(&token.lexeme),
+        None,
+        token.span.into(),
+    )
+}
+
+fn keyword(token_type: TokenType, lexeme: &str, span: SpanJson) -> Token {
+    Token::new(token_type, Symbol::intern(lexeme), None, span.into())
+}
+
+fn literal(value: Option<LiteralJson>, span: SpanJson) -> Token {
+    let (token_type, literal) = match value {
+        Some(LiteralJson::Bool(true)) => (TokenType::True, Literal::Bool(true)),
+        Some(LiteralJson::Bool(false)) => (TokenType::False, Literal::Bool(false)),
+        Some(LiteralJson::Number(n)) => (TokenType::Number, Literal::Number(n)),
+        Some(LiteralJson::NonFinite { number }) => {
+            let n = match number {
+                NonFinite::Infinity => f64::INFINITY,
+                NonFinite::NegativeInfinity => f64::NEG_INFINITY,
+                NonFinite::NaN => f64::NAN,
+            };
+            (TokenType::Number, Literal::Number(n))
+        }
+        Some(LiteralJson::String(s)) => (TokenType::String, Literal::String(Symbol::intern(&s))),
+        None => (TokenType::Nil, Literal::Nil),
+    };
+    let lexeme = match &literal {
+        Literal::String(s) => format!("\"{}\"", s),
+        literal => literal.to_string(),
+    };
+    Token::new(
+        token_type,
+        Symbol::intern(&lexeme),
+        Some(literal),
+        span.into(),
+    )
+}
@@ -0,0 +1,74 @@
+use crate::expression::expression::ExprId;
+use crate::statement::statement::StmtId;
+use std::marker::PhantomData;
+use std::ops::Index;
+
+/// An id of a node in an `Ast`.
+pub trait NodeId: Copy {
+    fn index(self) -> usize;
+}
+
+impl NodeId for ExprId {
+    fn index(self) -> usize {
+        ExprId::index(self)
+    }
+}
+
+impl NodeId for StmtId {
+    fn index(self) -> usize {
+        StmtId::index(self)
+    }
+}
+
+/// Data a pass attaches to nodes without touching the tree, such as the scope
+/// depth of a resolved variable or the inferred type of an expression. Nodes
+/// with nothing recorded simply have no entry.
+pub struct SideTable<I, V> {
+    values: Vec<Option<V>>,
+    ids: PhantomData<I>,
+}
+
+impl<I: NodeId, V> SideTable<I, V> {
+    pub fn new() -> Self {
+        SideTable {
+            values: Vec::new(),
+            ids: PhantomData,
+        }
+    }
+
+    /// Records `value` for `id`, returning what was there before.
+    pub fn insert(&mut self, id: I, value: V) -> Option<V> {
+        let index = id.index();
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+        self.values[index].replace(value)
+    }
+
+    pub fn get(&self, id: I) -> Option<&V> {
+        self.values.get(id.index()).and_then(Option::as_ref)
+    }
+
+    pub fn remove(&mut self, id: I) -> Option<V> {
+        self.values.get_mut(id.index()).and_then(Option::take)
+    }
+
+    pub fn contains(&self, id: I) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+impl<I: NodeId, V> Default for SideTable<I, V> {
+    fn default() -> Self {
+        SideTable::new()
+    }
+}
+
+impl<I: NodeId, V> Index<I> for SideTable<I, V> {
+    type Output = V;
+
+    /// Panics if nothing is recorded for `id`.
+    fn index(&self, id: I) -> &V {
+        self.get(id).expect("no entry for node")
+    }
+}
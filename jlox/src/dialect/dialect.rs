@@ -0,0 +1,37 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Which flavour of Lox the front end accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Lox exactly as the book defines it.
+    #[default]
+    Strict,
+    /// Lox plus this interpreter's extensions, such as hex, binary and octal
+    /// numbers, exponents and `_` digit separators.
+    Extended,
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Dialect::Strict),
+            "extended" => Ok(Dialect::Extended),
+            _ => Err(format!(
+                "unknown dialect '{}', expected 'strict' or 'extended'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dialect::Strict => write!(f, "strict"),
+            Dialect::Extended => write!(f, "extended"),
+        }
+    }
+}
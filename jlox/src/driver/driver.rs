@@ -0,0 +1,103 @@
+use crate::ast::arena::Ast;
+use crate::ast::ast_format::AstFormat;
+use crate::ast::serialize;
+use crate::dialect::dialect::Dialect;
+use crate::interpreter::interpreter::Interpreter;
+use crate::parser::parser::Parser;
+use crate::resolver::resolver::Resolver;
+use crate::scanner::scanner::{ScanResult, Scanner};
+use crate::statement::statement::StmtId;
+use std::rc::Rc;
+
+/// Scans, parses and runs a script or REPL line.
+///
+/// With `--emit-ast` it only prints the parsed program, so the output can be
+/// piped into other tools, and a JSON document loaded back with `--load-ast`.
+pub fn run(
+    source: &str,
+    dialect: Dialect,
+    emit_ast: Option<AstFormat>,
+    max_depth: usize,
+    max_height: usize,
+    interpreter: &mut Interpreter,
+) {
+    let scanner = Scanner::new(source).with_dialect(dialect);
+    let ScanResult { tokens, errors } = scanner.scan_tokens();
+    for e in &errors {
+        crate::errors::generic_error::error(e.span, &e.message);
+    }
+
+    // Parse into the interpreter's arena, so what it runs stays valid for the
+    // rest of the session.
+    let ast = Rc::clone(interpreter.ast());
+    let mut parser = Parser::new(&tokens, &ast)
+        .with_max_depth(max_depth)
+        .with_max_height(max_height);
+    match parser.parse() {
+        Ok(statements) => {
+            // Only run code that scanned cleanly.
+            run_statements(&ast, &statements, emit_ast, errors.is_empty(), interpreter);
+        }
+        Err(parse_errors) => {
+            for e in &parse_errors {
+                crate::errors::generic_error::token_error(&e.token, &e.message);
+            }
+        }
+    }
+
+    if emit_ast.is_some() {
+        return;
+    }
+
+    // Keep token printing for debugging, on stderr so program output stays
+    // clean.
+    eprintln!("\nTokens:");
+    for token in &tokens {
+        eprintln!("{:?}", token);
+    }
+}
+
+/// Runs a program serialized with `--emit-ast json`, skipping the scanner
+/// and parser. It still has to resolve before it runs.
+pub fn run_serialized(
+    json: &str,
+    emit_ast: Option<AstFormat>,
+    max_depth: usize,
+    max_height: usize,
+    interpreter: &mut Interpreter,
+) {
+    let ast = Rc::clone(interpreter.ast());
+    match serialize::from_json(json, &ast, max_depth, max_height) {
+        Ok(statements) => run_statements(&ast, &statements, emit_ast, true, interpreter),
+        Err(error) => eprintln!("Error loading AST: {}", error),
+    }
+}
+
+// Resolves the program, and interprets it if `execute` and it resolved
+// cleanly. With `--emit-ast` it only prints the program instead.
+fn run_statements(
+    ast: &Ast,
+    statements: &[StmtId],
+    emit_ast: Option<AstFormat>,
+    execute: bool,
+    interpreter: &mut Interpreter,
+) {
+    if let Some(format) = emit_ast {
+        println!("{}", format.print(ast, statements));
+        return;
+    }
+
+    eprintln!("\nAST:");
+    eprintln!("{}", AstFormat::Sexpr.print(ast, statements));
+    eprintln!();
+
+    if let Err(resolve_errors) = Resolver::new(interpreter).resolve(statements) {
+        for e in &resolve_errors {
+            crate::errors::generic_error::token_error(&e.token, &e.message);
+        }
+    } else if execute {
+        if let Err(error) = interpreter.interpret(statements) {
+            crate::errors::generic_error::runtime_error(&error);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::fmt;
+
+/// Why a serialized AST could not be loaded: malformed JSON, an unsupported
+/// schema version, or a tree the parser could never have produced.
+#[derive(Debug, Clone)]
+pub struct DeserializeError {
+    pub message: String,
+}
+
+impl DeserializeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        DeserializeError {
+            message: message.into(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for DeserializeError {
+    fn from(error: serde_json::Error) -> Self {
+        DeserializeError::new(error.to_string())
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
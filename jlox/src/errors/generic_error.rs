@@ -1,5 +1,22 @@
 use crate::errors::generic_report::report;
+use crate::errors::runtime_error::RuntimeError;
+use crate::scanner::token::{Span, Token, TokenType};
 
-pub fn error(line: usize, message: &str) {
-    report(line, "", message);
+pub fn error(span: Span, message: &str) {
+    report(span, "", message);
+}
+
+pub fn token_error(token: &Token, message: &str) {
+    if token.token_type == TokenType::Eof {
+        report(token.span, " at end", message);
+    } else {
+        report(token.span, &format!(" at '{}'", token.lexeme), message);
+    }
+}
+
+pub fn runtime_error(error: &RuntimeError) {
+    eprintln!(
+        "[line {}, column {}] Runtime error: {}",
+        error.token.span.line, error.token.span.column, error.message
+    );
 }
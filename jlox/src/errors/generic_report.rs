@@ -1,3 +1,8 @@
-pub fn report(line: usize, where_: &str, message: &str) {
-    eprintln!("[line {}] Error{}: {}", line, where_, message);
+use crate::scanner::token::Span;
+
+pub fn report(span: Span, where_: &str, message: &str) {
+    eprintln!(
+        "[line {}, column {}] Error{}: {}",
+        span.line, span.column, where_, message
+    );
 }
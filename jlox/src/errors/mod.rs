@@ -1,2 +1,7 @@
+pub mod deserialize_error;
 pub mod generic_error;
 pub mod generic_report;
+pub mod parse_error;
+pub mod resolve_error;
+pub mod runtime_error;
+pub mod scan_error;
@@ -0,0 +1,33 @@
+use crate::scanner::token::{Token, TokenType};
+use std::fmt;
+
+/// A syntax error. `token` is what the parser actually found and `message`
+/// says what it expected there instead.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub token: Token,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(token: Token, message: impl Into<String>) -> Self {
+        ParseError {
+            token,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.token_type == TokenType::Eof {
+            write!(f, "[{}] at end: {}", self.token.span, self.message)
+        } else {
+            write!(
+                f,
+                "[{}] at '{}': {}",
+                self.token.span, self.token.lexeme, self.message
+            )
+        }
+    }
+}
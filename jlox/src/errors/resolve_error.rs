@@ -0,0 +1,29 @@
+use crate::scanner::token::Token;
+use std::fmt;
+
+/// A static error found after parsing, such as `this` outside a class.
+/// `token` is the name or keyword the error is about.
+#[derive(Debug, Clone)]
+pub struct ResolveError {
+    pub token: Token,
+    pub message: String,
+}
+
+impl ResolveError {
+    pub fn new(token: &Token, message: impl Into<String>) -> Self {
+        ResolveError {
+            token: token.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] at '{}': {}",
+            self.token.span, self.token.lexeme, self.message
+        )
+    }
+}
@@ -0,0 +1,25 @@
+use crate::scanner::token::Token;
+use std::fmt;
+
+/// An error raised while running a program. `token` is the operator, name or
+/// parenthesis that was being evaluated when things went wrong.
+#[derive(Debug, Clone)]
+pub struct RuntimeError {
+    pub token: Token,
+    pub message: String,
+}
+
+impl RuntimeError {
+    pub fn new(token: &Token, message: impl Into<String>) -> Self {
+        RuntimeError {
+            token: token.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.token.span, self.message)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::scanner::token::Span;
+use std::fmt;
+
+/// A lexical error, located at the characters that caused it.
+#[derive(Debug, Clone)]
+pub struct ScanError {
+    pub span: Span,
+    pub message: String,
+}
+
+impl ScanError {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        ScanError {
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.span, self.message)
+    }
+}
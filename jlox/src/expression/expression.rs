@@ -1,42 +1,181 @@
+use crate::ast::arena::Ast;
 use crate::scanner::token::Token;
+use crate::statement::statement::Function;
+use std::rc::Rc;
+
+/// Identifies one expression in an `Ast`. Passes such as the resolver record
+/// what they learn about a node in side tables keyed by this id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(u32);
+
+impl ExprId {
+    pub(crate) fn from_index(index: usize) -> ExprId {
+        ExprId(u32::try_from(index).expect("too many expressions"))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
 
 pub enum Expr {
+    Assign {
+        name: Token,
+        value: ExprId,
+    },
     Binary {
-        left: Box<Expr>,
+        left: ExprId,
         operator: Token,
-        right: Box<Expr>,
+        right: ExprId,
+    },
+    /// `paren` is the closing parenthesis, used to locate runtime errors
+    /// raised by the call.
+    Call {
+        callee: ExprId,
+        paren: Token,
+        arguments: Vec<ExprId>,
+    },
+    /// `left, right`: evaluates both, left first, and yields `right`.
+    Comma {
+        left: ExprId,
+        right: ExprId,
+    },
+    /// `condition ? then_branch : else_branch`; only the chosen branch is
+    /// evaluated.
+    Conditional {
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    },
+    /// An anonymous `fun (params) { body }`. It closes over its surroundings
+    /// just like a named function.
+    Function(Rc<Function>),
+    Get {
+        object: ExprId,
+        name: Token,
+    },
+    Grouping {
+        expression: ExprId,
     },
     Literal {
         value: Token,
     },
+    /// `and` / `or`. Kept apart from `Binary` because the right operand is only
+    /// evaluated when the left one doesn't already decide the result.
+    Logical {
+        left: ExprId,
+        operator: Token,
+        right: ExprId,
+    },
+    Set {
+        object: ExprId,
+        name: Token,
+        value: ExprId,
+    },
+    Super {
+        keyword: Token,
+        method: Token,
+    },
+    This {
+        keyword: Token,
+    },
+    Unary {
+        operator: Token,
+        right: ExprId,
+    },
+    Variable {
+        name: Token,
+    },
+    /// An interpolated string: literal segments and embedded expressions, in
+    /// order, each stringified and concatenated.
+    Interpolation {
+        parts: Vec<ExprId>,
+    },
 }
 
-impl Expr {
-    #[allow(dead_code)]
-    pub fn binary(left: Expr, operator: Token, right: Expr) -> Expr {
-        Expr::Binary {
-            left: Box::new(left),
-            operator,
-            right: Box::new(right),
-        }
-    }
-}
-
+/// Every method gets the id of the node it visits first, followed by the
+/// node's fields, with children as ids for the visitor to look up or accept.
 pub trait ExprVisitor<R> {
-    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> R;
-    fn visit_literal_expr(&mut self, value: &Token) -> R;
+    fn visit_assign_expr(&mut self, id: ExprId, name: &Token, value: ExprId) -> R;
+    fn visit_binary_expr(&mut self, id: ExprId, left: ExprId, operator: &Token, right: ExprId)
+        -> R;
+    fn visit_call_expr(
+        &mut self,
+        id: ExprId,
+        callee: ExprId,
+        paren: &Token,
+        arguments: &[ExprId],
+    ) -> R;
+    fn visit_comma_expr(&mut self, id: ExprId, left: ExprId, right: ExprId) -> R;
+    fn visit_conditional_expr(
+        &mut self,
+        id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> R;
+    fn visit_function_expr(&mut self, id: ExprId, function: &Rc<Function>) -> R;
+    fn visit_get_expr(&mut self, id: ExprId, object: ExprId, name: &Token) -> R;
+    fn visit_grouping_expr(&mut self, id: ExprId, expression: ExprId) -> R;
+    fn visit_literal_expr(&mut self, id: ExprId, value: &Token) -> R;
+    fn visit_logical_expr(
+        &mut self,
+        id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> R;
+    fn visit_set_expr(&mut self, id: ExprId, object: ExprId, name: &Token, value: ExprId) -> R;
+    fn visit_super_expr(&mut self, id: ExprId, keyword: &Token, method: &Token) -> R;
+    fn visit_this_expr(&mut self, id: ExprId, keyword: &Token) -> R;
+    fn visit_unary_expr(&mut self, id: ExprId, operator: &Token, right: ExprId) -> R;
+    fn visit_variable_expr(&mut self, id: ExprId, name: &Token) -> R;
+    fn visit_interpolation_expr(&mut self, id: ExprId, parts: &[ExprId]) -> R;
 }
 
-impl Expr {
-    pub fn accept<R>(&self, visitor: &mut dyn ExprVisitor<R>) -> R {
-        match self {
+impl ExprId {
+    /// Dispatches to the `visitor` method for this node in `ast`. The node
+    /// stays borrowed during the call, so the visitor may read the arena but
+    /// not add to it.
+    pub fn accept<R>(self, ast: &Ast, visitor: &mut dyn ExprVisitor<R>) -> R {
+        let id = self;
+        match &*ast.expr(id) {
+            Expr::Assign { name, value } => visitor.visit_assign_expr(id, name, *value),
             Expr::Binary {
                 left,
                 operator,
                 right,
-            } => visitor.visit_binary_expr(left, operator, right),
-            Expr::Literal { value } => visitor.visit_literal_expr(value),
+            } => visitor.visit_binary_expr(id, *left, operator, *right),
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => visitor.visit_call_expr(id, *callee, paren, arguments),
+            Expr::Comma { left, right } => visitor.visit_comma_expr(id, *left, *right),
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => visitor.visit_conditional_expr(id, *condition, *then_branch, *else_branch),
+            Expr::Function(function) => visitor.visit_function_expr(id, function),
+            Expr::Get { object, name } => visitor.visit_get_expr(id, *object, name),
+            Expr::Grouping { expression } => visitor.visit_grouping_expr(id, *expression),
+            Expr::Literal { value } => visitor.visit_literal_expr(id, value),
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            } => visitor.visit_logical_expr(id, *left, operator, *right),
+            Expr::Set {
+                object,
+                name,
+                value,
+            } => visitor.visit_set_expr(id, *object, name, *value),
+            Expr::Super { keyword, method } => visitor.visit_super_expr(id, keyword, method),
+            Expr::This { keyword } => visitor.visit_this_expr(id, keyword),
+            Expr::Unary { operator, right } => visitor.visit_unary_expr(id, operator, *right),
+            Expr::Variable { name } => visitor.visit_variable_expr(id, name),
+            Expr::Interpolation { parts } => visitor.visit_interpolation_expr(id, parts),
         }
     }
 }
-
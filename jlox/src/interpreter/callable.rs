@@ -0,0 +1,121 @@
+use crate::errors::runtime_error::RuntimeError;
+use crate::interpreter::class::LoxInstance;
+use crate::interpreter::environment::Environment;
+use crate::interpreter::interpreter::{Interpreter, Unwind};
+use crate::interpreter::value::Value;
+use crate::scanner::symbol::Symbol;
+use crate::statement::statement::Function;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Anything that can appear before `(` in a call expression. The interpreter
+/// checks the argument count against `arity` before calling.
+pub trait Callable {
+    fn arity(&self) -> usize;
+    fn call(
+        self: Rc<Self>,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError>;
+}
+
+/// A function declared in Lox, together with the scope it was declared in.
+pub struct LoxFunction {
+    declaration: Rc<Function>,
+    closure: Rc<RefCell<Environment>>,
+    /// Set for a class's `init` method, which always returns `this`.
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(
+        declaration: Rc<Function>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> Self {
+        LoxFunction {
+            declaration,
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// Returns a copy of this method whose `this` is `instance`.
+    pub fn bind(&self, instance: Rc<LoxInstance>) -> LoxFunction {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment.define(Symbol::intern("this"), Value::Instance(instance));
+        LoxFunction::new(
+            Rc::clone(&self.declaration),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    /// The declared name, or `None` for a lambda.
+    pub fn name(&self) -> Option<&str> {
+        self.declaration
+            .name
+            .as_ref()
+            .map(|name| name.lexeme.as_str())
+    }
+}
+
+impl Callable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.declaration.params.len()
+    }
+
+    fn call(
+        self: Rc<Self>,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        // Each call gets its own scope, so recursion doesn't clobber parameters.
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        for (param, argument) in self.declaration.params.iter().zip(arguments) {
+            environment.define(param.lexeme, argument);
+        }
+
+        let result =
+            interpreter.execute_block(&self.declaration.body, Rc::new(RefCell::new(environment)));
+
+        match result {
+            // `init()` hands back the instance, even when called directly or
+            // left with a bare `return;`.
+            Ok(()) | Err(Unwind::Return(_)) if self.is_initializer => {
+                Ok(self.closure.borrow().get_at(0, Symbol::intern("this")))
+            }
+            Ok(()) => Ok(Value::Nil),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(error)) => Err(error),
+            // The resolver keeps loop jumps from crossing a function boundary.
+            Err(Unwind::Break | Unwind::Continue) => unreachable!("jump out of a function"),
+        }
+    }
+}
+
+/// A function implemented in Rust and exposed to Lox as a global.
+pub struct NativeFunction {
+    arity: usize,
+    function: fn(&[Value]) -> Value,
+}
+
+impl NativeFunction {
+    pub fn new(arity: usize, function: fn(&[Value]) -> Value) -> Self {
+        NativeFunction { arity, function }
+    }
+}
+
+impl Callable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        self: Rc<Self>,
+        _: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        Ok((self.function)(&arguments))
+    }
+}
@@ -0,0 +1,112 @@
+use crate::errors::runtime_error::RuntimeError;
+use crate::interpreter::callable::{Callable, LoxFunction};
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::value::Value;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::Token;
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A class. Calling it creates an instance and runs `init` on it, if the class
+/// or one of its superclasses defines one.
+pub struct LoxClass {
+    pub name: Symbol,
+    superclass: Option<Rc<LoxClass>>,
+    methods: FxHashMap<Symbol, Rc<LoxFunction>>,
+}
+
+impl LoxClass {
+    pub fn new(
+        name: Symbol,
+        superclass: Option<Rc<LoxClass>>,
+        methods: FxHashMap<Symbol, Rc<LoxFunction>>,
+    ) -> Self {
+        LoxClass {
+            name,
+            superclass,
+            methods,
+        }
+    }
+
+    /// Looks `name` up on this class, then on each superclass in turn.
+    pub fn find_method(&self, name: Symbol) -> Option<Rc<LoxFunction>> {
+        match self.methods.get(&name) {
+            Some(method) => Some(Rc::clone(method)),
+            None => self.superclass.as_ref()?.find_method(name),
+        }
+    }
+
+    fn initializer(&self) -> Option<Rc<LoxFunction>> {
+        self.find_method(Symbol::intern("init"))
+    }
+}
+
+impl Callable for LoxClass {
+    fn arity(&self) -> usize {
+        self.initializer().map_or(0, |init| init.arity())
+    }
+
+    fn call(
+        self: Rc<Self>,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let instance = Rc::new(LoxInstance::new(Rc::clone(&self)));
+        if let Some(initializer) = self.initializer() {
+            Rc::new(initializer.bind(Rc::clone(&instance))).call(interpreter, arguments)?;
+        }
+        Ok(Value::Instance(instance))
+    }
+}
+
+/// An object created by calling a class. Fields can be added at any time.
+pub struct LoxInstance {
+    pub class: Rc<LoxClass>,
+    fields: RefCell<FxHashMap<Symbol, Value>>,
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        LoxInstance {
+            class,
+            fields: RefCell::default(),
+        }
+    }
+
+    /// Fields shadow methods; methods come back bound to this instance.
+    pub fn get(self: &Rc<Self>, name: &Token) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.fields.borrow().get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match self.class.find_method(name.lexeme) {
+            Some(method) => Ok(Value::Function(Rc::new(method.bind(Rc::clone(self))))),
+            None => Err(RuntimeError::new(
+                name,
+                format!("Undefined property '{}'.", name.lexeme),
+            )),
+        }
+    }
+
+    pub fn set(&self, name: &Token, value: Value) {
+        self.fields.borrow_mut().insert(name.lexeme, value);
+    }
+}
+
+impl Drop for LoxInstance {
+    // Dropping a field can drop another instance, so a long chain of them,
+    // like a linked list, would otherwise recurse once per link. Instead the
+    // fields go on a worklist, and instances this was the last owner of are
+    // emptied onto it before they are dropped.
+    fn drop(&mut self) {
+        let mut pending: Vec<Value> = self.fields.get_mut().drain().map(|(_, v)| v).collect();
+        while let Some(value) = pending.pop() {
+            if let Value::Instance(instance) = value {
+                if let Ok(mut instance) = Rc::try_unwrap(instance) {
+                    pending.extend(instance.fields.get_mut().drain().map(|(_, v)| v));
+                }
+            }
+        }
+    }
+}
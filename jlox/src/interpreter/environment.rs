@@ -0,0 +1,103 @@
+use crate::errors::runtime_error::RuntimeError;
+use crate::interpreter::value::Value;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::Token;
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// One scope of variable bindings. Scopes are shared because closures keep the
+/// scope they were created in alive after the block that made it has finished.
+#[derive(Default)]
+pub struct Environment {
+    values: FxHashMap<Symbol, Value>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment::default()
+    }
+
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            values: FxHashMap::default(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    /// Binds `name` in this scope, replacing any earlier binding of the same
+    /// name.
+    pub fn define(&mut self, name: Symbol, value: Value) {
+        self.values.insert(name, value);
+    }
+
+    /// Reads a variable the resolver found `distance` scopes out, without
+    /// searching by name along the way.
+    pub fn get_at(&self, distance: usize, name: Symbol) -> Value {
+        if distance == 0 {
+            match self.values.get(&name) {
+                Some(value) => value.clone(),
+                None => panic!("resolved variable '{}' is not defined", name),
+            }
+        } else {
+            self.enclosing().borrow().get_at(distance - 1, name)
+        }
+    }
+
+    /// Like `get_at`, but `None` if there is no scope `distance` out or it
+    /// doesn't bind `name`.
+    pub fn find_at(&self, distance: usize, name: Symbol) -> Option<Value> {
+        if distance == 0 {
+            self.values.get(&name).cloned()
+        } else {
+            self.enclosing
+                .as_ref()?
+                .borrow()
+                .find_at(distance - 1, name)
+        }
+    }
+
+    pub fn assign_at(&mut self, distance: usize, name: Symbol, value: Value) {
+        if distance == 0 {
+            self.values.insert(name, value);
+        } else {
+            self.enclosing()
+                .borrow_mut()
+                .assign_at(distance - 1, name, value);
+        }
+    }
+
+    pub fn enclosing(&self) -> &Rc<RefCell<Environment>> {
+        self.enclosing
+            .as_ref()
+            .expect("resolved scope is deeper than the environment chain")
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.values.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(undefined(name)),
+        }
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        if let Some(slot) = self.values.get_mut(&name.lexeme) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(undefined(name)),
+        }
+    }
+}
+
+fn undefined(name: &Token) -> RuntimeError {
+    RuntimeError::new(name, format!("Undefined variable '{}'.", name.lexeme))
+}
@@ -0,0 +1,603 @@
+use crate::ast::arena::Ast;
+use crate::ast::side_table::SideTable;
+use crate::errors::runtime_error::RuntimeError;
+use crate::expression::expression::{Expr, ExprId, ExprVisitor};
+use crate::interpreter::callable::{LoxFunction, NativeFunction};
+use crate::interpreter::class::LoxClass;
+use crate::interpreter::environment::Environment;
+use crate::interpreter::value::Value;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::{Token, TokenType};
+use crate::statement::statement::{Function, StmtId, StmtVisitor};
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How deeply evaluation may nest before a call fails with "Stack overflow.".
+/// Every expression and statement being evaluated counts, not just calls: a
+/// call made from deep inside an expression takes more native stack than one
+/// at the top, and the native stack is what actually runs out.
+const MAX_DEPTH: usize = 1024;
+
+/// Why execution of a statement stopped early: an error, a `return` carrying
+/// its value back up to the function call, or a `break` or `continue` headed
+/// for the innermost loop.
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
+    Break,
+    Continue,
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+/// Tree-walking interpreter. Globals and the arena survive between calls to
+/// `interpret`, so one interpreter can back a whole REPL session.
+pub struct Interpreter {
+    /// Holds every node the interpreter runs. Each parse adds to it, so
+    /// functions declared earlier in a session can still be called.
+    ast: Rc<Ast>,
+    globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    /// How many scopes out each resolved local lives, filled in by the
+    /// resolver. Variables missing from here are globals.
+    locals: SideTable<ExprId, usize>,
+    /// Expressions and statements currently being evaluated inside one
+    /// another, across calls.
+    depth: usize,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        globals.borrow_mut().define(
+            Symbol::intern("clock"),
+            Value::Native(Rc::new(NativeFunction::new(0, clock))),
+        );
+
+        Interpreter {
+            ast: Rc::new(Ast::new()),
+            environment: Rc::clone(&globals),
+            globals,
+            locals: SideTable::new(),
+            depth: 0,
+        }
+    }
+
+    /// The arena that code for this interpreter must be parsed into.
+    pub fn ast(&self) -> &Rc<Ast> {
+        &self.ast
+    }
+
+    /// Records that the variable expression `id` refers to a local `depth`
+    /// scopes out from where it is used.
+    pub fn resolve(&mut self, id: ExprId, depth: usize) {
+        self.locals.insert(id, depth);
+    }
+
+    fn look_up_variable(&self, id: ExprId, name: &Token) -> Result<Value, RuntimeError> {
+        match self.locals.get(id) {
+            Some(&distance) => Ok(self.environment.borrow().get_at(distance, name.lexeme)),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    /// Runs `statements` in order, stopping at the first runtime error.
+    pub fn interpret(&mut self, statements: &[StmtId]) -> Result<(), RuntimeError> {
+        for &statement in statements {
+            match self.execute(statement) {
+                Ok(()) => {}
+                Err(Unwind::Error(error)) => return Err(error),
+                // The resolver only accepts these inside a function or loop.
+                Err(Unwind::Return(_)) => unreachable!("return outside of a function"),
+                Err(Unwind::Break | Unwind::Continue) => unreachable!("jump outside of a loop"),
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, stmt: StmtId) -> Result<(), Unwind> {
+        let ast = Rc::clone(&self.ast);
+        self.depth += 1;
+        let result = stmt.accept(&ast, self);
+        self.depth -= 1;
+        result
+    }
+
+    fn evaluate(&mut self, expr: ExprId) -> Result<Value, RuntimeError> {
+        let ast = Rc::clone(&self.ast);
+        self.depth += 1;
+        let result = expr.accept(&ast, self);
+        self.depth -= 1;
+        result
+    }
+
+    // Capture the scope the function is created in, not the one it is later
+    // called from.
+    fn make_function(&self, function: &Rc<Function>) -> Value {
+        Value::Function(Rc::new(LoxFunction::new(
+            Rc::clone(function),
+            Rc::clone(&self.environment),
+            false,
+        )))
+    }
+
+    /// Runs `statements` in `environment`, restoring the current scope
+    /// afterwards however the block was left.
+    pub fn execute_block(
+        &mut self,
+        statements: &[StmtId],
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<(), Unwind> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = statements
+            .iter()
+            .try_for_each(|&statement| self.execute(statement));
+        self.environment = previous;
+        result
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+// clock() -> seconds since the Unix epoch, for timing scripts.
+fn clock(_: &[Value]) -> Value {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Value::Number(now.as_secs_f64())
+}
+
+fn number_operand(operator: &Token, operand: &Value) -> Result<f64, RuntimeError> {
+    match operand {
+        Value::Number(n) => Ok(*n),
+        _ => Err(RuntimeError::new(operator, "Operand must be a number.")),
+    }
+}
+
+fn number_operands(
+    operator: &Token,
+    left: &Value,
+    right: &Value,
+) -> Result<(f64, f64), RuntimeError> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => Ok((*l, *r)),
+        _ => Err(RuntimeError::new(operator, "Operands must be numbers.")),
+    }
+}
+
+impl ExprVisitor<Result<Value, RuntimeError>> for Interpreter {
+    fn visit_assign_expr(
+        &mut self,
+        id: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let value = self.evaluate(value)?;
+        match self.locals.get(id) {
+            Some(&distance) => {
+                self.environment
+                    .borrow_mut()
+                    .assign_at(distance, name.lexeme, value.clone());
+            }
+            None => self.globals.borrow_mut().assign(name, value.clone())?,
+        }
+        Ok(value)
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let left = self.evaluate(left)?;
+        let right = self.evaluate(right)?;
+
+        let value = match operator.token_type {
+            TokenType::Plus => match (&left, &right) {
+                (Value::Number(l), Value::Number(r)) => Value::Number(l + r),
+                (Value::String(l), Value::String(r)) => Value::String(format!("{}{}", l, r).into()),
+                _ => {
+                    return Err(RuntimeError::new(
+                        operator,
+                        "Operands must be two numbers or two strings.",
+                    ))
+                }
+            },
+            TokenType::Minus => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Number(l - r)
+            }
+            TokenType::Star => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Number(l * r)
+            }
+            TokenType::Slash => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Number(l / r)
+            }
+            TokenType::Greater => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Bool(l > r)
+            }
+            TokenType::GreaterEqual => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Bool(l >= r)
+            }
+            TokenType::Less => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Bool(l < r)
+            }
+            TokenType::LessEqual => {
+                let (l, r) = number_operands(operator, &left, &right)?;
+                Value::Bool(l <= r)
+            }
+            TokenType::EqualEqual => Value::Bool(left == right),
+            TokenType::BangEqual => Value::Bool(left != right),
+            _ => unreachable!("parser produced binary operator {:?}", operator.token_type),
+        };
+        Ok(value)
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        _id: ExprId,
+        callee: ExprId,
+        paren: &Token,
+        arguments: &[ExprId],
+    ) -> Result<Value, RuntimeError> {
+        let callee = self.evaluate(callee)?;
+
+        let mut values = Vec::with_capacity(arguments.len());
+        for &argument in arguments {
+            values.push(self.evaluate(argument)?);
+        }
+
+        let Some(function) = callee.as_callable() else {
+            return Err(RuntimeError::new(
+                paren,
+                "Can only call functions and classes.",
+            ));
+        };
+        if values.len() != function.arity() {
+            return Err(RuntimeError::new(
+                paren,
+                format!(
+                    "Expected {} arguments but got {}.",
+                    function.arity(),
+                    values.len()
+                ),
+            ));
+        }
+
+        // Only calls let a running program nest evaluation deeper, so this is
+        // the one place to check the limit.
+        if self.depth >= MAX_DEPTH {
+            return Err(RuntimeError::new(paren, "Stack overflow."));
+        }
+
+        function.call(self, values)
+    }
+
+    fn visit_comma_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        right: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        self.evaluate(left)?;
+        self.evaluate(right)
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        _id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        if self.evaluate(condition)?.is_truthy() {
+            self.evaluate(then_branch)
+        } else {
+            self.evaluate(else_branch)
+        }
+    }
+
+    fn visit_function_expr(
+        &mut self,
+        _id: ExprId,
+        function: &Rc<Function>,
+    ) -> Result<Value, RuntimeError> {
+        Ok(self.make_function(function))
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        _id: ExprId,
+        object: ExprId,
+        name: &Token,
+    ) -> Result<Value, RuntimeError> {
+        match self.evaluate(object)? {
+            Value::Instance(instance) => instance.get(name),
+            _ => Err(RuntimeError::new(name, "Only instances have properties.")),
+        }
+    }
+
+    fn visit_grouping_expr(
+        &mut self,
+        _id: ExprId,
+        expression: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        self.evaluate(expression)
+    }
+
+    fn visit_literal_expr(&mut self, _id: ExprId, value: &Token) -> Result<Value, RuntimeError> {
+        Ok(value.literal.as_ref().map_or(Value::Nil, Value::from))
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        _id: ExprId,
+        left: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let left = self.evaluate(left)?;
+
+        // Short-circuit, yielding the operand that decided the result.
+        if operator.token_type == TokenType::Or {
+            if left.is_truthy() {
+                return Ok(left);
+            }
+        } else if !left.is_truthy() {
+            return Ok(left);
+        }
+
+        self.evaluate(right)
+    }
+
+    fn visit_set_expr(
+        &mut self,
+        _id: ExprId,
+        object: ExprId,
+        name: &Token,
+        value: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let Value::Instance(instance) = self.evaluate(object)? else {
+            return Err(RuntimeError::new(name, "Only instances have fields."));
+        };
+
+        let value = self.evaluate(value)?;
+        instance.set(name, value.clone());
+        Ok(value)
+    }
+
+    fn visit_super_expr(
+        &mut self,
+        id: ExprId,
+        keyword: &Token,
+        method: &Token,
+    ) -> Result<Value, RuntimeError> {
+        // A parsed program binds `super` to the superclass here, with `this`
+        // in the scope just inside it, but a tree loaded with --load-ast has
+        // only been resolved, so check what is actually bound.
+        let environment = self.environment.borrow();
+        let bound = self.locals.get(id).and_then(|&distance| {
+            let superclass = environment.find_at(distance, Symbol::intern("super"))?;
+            let object = environment.find_at(distance.checked_sub(1)?, Symbol::intern("this"))?;
+            Some((superclass, object))
+        });
+        let Some((Value::Class(superclass), Value::Instance(object))) = bound else {
+            return Err(RuntimeError::new(
+                keyword,
+                "'super' is not bound to a superclass here.",
+            ));
+        };
+
+        match superclass.find_method(method.lexeme) {
+            Some(found) => Ok(Value::Function(Rc::new(found.bind(object)))),
+            None => Err(RuntimeError::new(
+                method,
+                format!("Undefined property '{}'.", method.lexeme),
+            )),
+        }
+    }
+
+    fn visit_this_expr(&mut self, id: ExprId, keyword: &Token) -> Result<Value, RuntimeError> {
+        self.look_up_variable(id, keyword)
+    }
+
+    fn visit_unary_expr(
+        &mut self,
+        _id: ExprId,
+        operator: &Token,
+        right: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let right = self.evaluate(right)?;
+
+        match operator.token_type {
+            TokenType::Bang => Ok(Value::Bool(!right.is_truthy())),
+            TokenType::Minus => Ok(Value::Number(-number_operand(operator, &right)?)),
+            _ => unreachable!("parser produced unary operator {:?}", operator.token_type),
+        }
+    }
+
+    fn visit_variable_expr(&mut self, id: ExprId, name: &Token) -> Result<Value, RuntimeError> {
+        self.look_up_variable(id, name)
+    }
+
+    fn visit_interpolation_expr(
+        &mut self,
+        _id: ExprId,
+        parts: &[ExprId],
+    ) -> Result<Value, RuntimeError> {
+        let mut out = String::new();
+        for &part in parts {
+            out.push_str(&self.evaluate(part)?.to_string());
+        }
+        Ok(Value::String(out.into()))
+    }
+}
+
+impl StmtVisitor<Result<(), Unwind>> for Interpreter {
+    fn visit_block_stmt(&mut self, _id: StmtId, statements: &[StmtId]) -> Result<(), Unwind> {
+        let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+        self.execute_block(statements, Rc::new(RefCell::new(environment)))
+    }
+
+    fn visit_break_stmt(&mut self, _id: StmtId, _keyword: &Token) -> Result<(), Unwind> {
+        Err(Unwind::Break)
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        _id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> Result<(), Unwind> {
+        let superclass = match superclass {
+            Some(expr) => match self.evaluate(expr)? {
+                Value::Class(class) => Some(class),
+                _ => {
+                    let ast = Rc::clone(&self.ast);
+                    let token = match &*ast.expr(expr) {
+                        Expr::Variable { name } => name.clone(),
+                        _ => name.clone(),
+                    };
+                    return Err(RuntimeError::new(&token, "Superclass must be a class.").into());
+                }
+            },
+            None => None,
+        };
+
+        self.environment
+            .borrow_mut()
+            .define(name.lexeme, Value::Nil);
+
+        // Methods of a subclass close over an extra scope that binds `super`.
+        let previous = Rc::clone(&self.environment);
+        if let Some(superclass) = &superclass {
+            let mut environment = Environment::with_enclosing(Rc::clone(&self.environment));
+            environment.define(Symbol::intern("super"), Value::Class(Rc::clone(superclass)));
+            self.environment = Rc::new(RefCell::new(environment));
+        }
+
+        let mut table = FxHashMap::default();
+        for method in methods {
+            let Some(method_name) = &method.name else {
+                continue;
+            };
+            let function = LoxFunction::new(
+                Rc::clone(method),
+                Rc::clone(&self.environment),
+                method_name.lexeme.as_str() == "init",
+            );
+            table.insert(method_name.lexeme, Rc::new(function));
+        }
+        let class = LoxClass::new(name.lexeme, superclass, table);
+
+        self.environment = previous;
+        self.environment
+            .borrow_mut()
+            .assign(name, Value::Class(Rc::new(class)))?;
+        Ok(())
+    }
+
+    fn visit_continue_stmt(&mut self, _id: StmtId, _keyword: &Token) -> Result<(), Unwind> {
+        Err(Unwind::Continue)
+    }
+
+    fn visit_expression_stmt(&mut self, _id: StmtId, expression: ExprId) -> Result<(), Unwind> {
+        self.evaluate(expression)?;
+        Ok(())
+    }
+
+    fn visit_function_stmt(&mut self, _id: StmtId, function: &Rc<Function>) -> Result<(), Unwind> {
+        let value = self.make_function(function);
+        if let Some(name) = &function.name {
+            self.environment.borrow_mut().define(name.lexeme, value);
+        }
+        Ok(())
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> Result<(), Unwind> {
+        if self.evaluate(condition)?.is_truthy() {
+            self.execute(then_branch)
+        } else if let Some(else_branch) = else_branch {
+            self.execute(else_branch)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn visit_print_stmt(&mut self, _id: StmtId, expression: ExprId) -> Result<(), Unwind> {
+        let value = self.evaluate(expression)?;
+        println!("{}", value);
+        Ok(())
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _id: StmtId,
+        _keyword: &Token,
+        value: Option<ExprId>,
+    ) -> Result<(), Unwind> {
+        let value = match value {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Nil,
+        };
+        Err(Unwind::Return(value))
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        _id: StmtId,
+        name: &Token,
+        initializer: Option<ExprId>,
+    ) -> Result<(), Unwind> {
+        let value = match initializer {
+            Some(initializer) => self.evaluate(initializer)?,
+            None => Value::Nil,
+        };
+        self.environment.borrow_mut().define(name.lexeme, value);
+        Ok(())
+    }
+
+    fn visit_while_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> Result<(), Unwind> {
+        while self.evaluate(condition)?.is_truthy() {
+            match self.execute(body) {
+                Ok(()) | Err(Unwind::Continue) => {}
+                Err(Unwind::Break) => break,
+                Err(unwind) => return Err(unwind),
+            }
+            if let Some(increment) = increment {
+                self.evaluate(increment)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod callable;
+pub mod class;
+pub mod environment;
+pub mod interpreter;
+pub mod value;
@@ -0,0 +1,90 @@
+use crate::interpreter::callable::{Callable, LoxFunction, NativeFunction};
+use crate::interpreter::class::{LoxClass, LoxInstance};
+use crate::scanner::token::Literal;
+use std::fmt;
+use std::rc::Rc;
+
+/// A Lox value at runtime. Strings, functions, classes and instances are
+/// reference counted, so copying a value around is cheap.
+#[derive(Clone)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    String(Rc<str>),
+    Function(Rc<LoxFunction>),
+    Native(Rc<NativeFunction>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<LoxInstance>),
+}
+
+impl Value {
+    /// `nil` and `false` are falsey; everything else is truthy.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Bool(false))
+    }
+
+    pub fn as_callable(&self) -> Option<Rc<dyn Callable>> {
+        match self {
+            Value::Function(function) => Some(Rc::clone(function) as Rc<dyn Callable>),
+            Value::Native(native) => Some(Rc::clone(native) as Rc<dyn Callable>),
+            Value::Class(class) => Some(Rc::clone(class) as Rc<dyn Callable>),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Literal> for Value {
+    fn from(literal: &Literal) -> Self {
+        match literal {
+            Literal::Number(n) => Value::Number(*n),
+            Literal::String(s) => Value::String(Rc::from(s.as_str())),
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Nil => Value::Nil,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            // Functions, classes and instances are only equal to themselves.
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::Native(a), Value::Native(b)) => Rc::ptr_eq(a, b),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Function(function) => match function.name() {
+                Some(name) => write!(f, "<fn {}>", name),
+                None => write!(f, "<fn>"),
+            },
+            Value::Native(_) => write!(f, "<native fn>"),
+            Value::Class(class) => write!(f, "{}", class.name),
+            Value::Instance(instance) => write!(f, "{} instance", instance.class.name),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{:?}", s),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
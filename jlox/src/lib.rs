@@ -0,0 +1,14 @@
+#![allow(clippy::module_inception)]
+
+pub mod argparser;
+pub mod ast;
+pub mod dialect;
+pub mod driver;
+pub mod errors;
+pub mod expression;
+pub mod interpreter;
+pub mod parser;
+pub mod resolver;
+pub mod scanner;
+pub mod statement;
+pub mod utils;
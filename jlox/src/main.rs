@@ -1,31 +1,36 @@
 use anyhow::Result;
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, Read, Write};
 
-mod argparser;
-mod errors;
-mod scanner;
-mod utils;
-mod expression;
-mod ast;
-mod parser;
+use jlox::argparser::argparser::{parse_args, Args};
+use jlox::driver::driver::{run, run_serialized};
+use jlox::interpreter::interpreter::Interpreter;
+use jlox::utils::read_file::read_file;
 
-use argparser::argparser::parse_args;
-use scanner::scanner::run;
-use utils::read_file::read_file;
-
-fn run_file(file: &str) {
+fn run_file(file: &str, args: &Args) {
     let contents = read_file(file).expect("Error reading file");
-    println!("Running file: {}", file);
-    run(contents);
+    eprintln!("Running file: {}", file);
+    run_source(&contents, args, &mut Interpreter::new());
 }
 
-fn run_prompt() -> Result<()> {
+fn run_prompt(args: &Args) -> Result<()> {
     let stdin = io::stdin();
     let mut reader = stdin.lock();
+    let mut interpreter = Interpreter::new();
+
+    // A JSON document spans many lines, so read all of it at once.
+    if args.load_ast {
+        let mut json = String::new();
+        reader.read_to_string(&mut json)?;
+        run_source(&json, args, &mut interpreter);
+        return Ok(());
+    }
 
     loop {
-        print!("> ");
-        io::stdout().flush()?; // Ensure the prompt is displayed immediately
+        // Keep stdout clean when it carries the emitted tree.
+        if args.emit_ast.is_none() {
+            print!("> ");
+            io::stdout().flush()?; // Ensure the prompt is displayed immediately
+        }
 
         let mut line = String::new();
         let bytes_read = reader.read_line(&mut line)?;
@@ -34,16 +39,38 @@ fn run_prompt() -> Result<()> {
             break; // EOF reached
         }
 
-        run(line.trim().to_string());
+        run_source(line.trim(), args, &mut interpreter);
     }
 
     Ok(())
 }
 
+// With --load-ast the script, or all of stdin, is a serialized AST.
+fn run_source(source: &str, args: &Args, interpreter: &mut Interpreter) {
+    if args.load_ast {
+        run_serialized(
+            source,
+            args.emit_ast,
+            args.max_depth,
+            args.max_height,
+            interpreter,
+        );
+    } else {
+        run(
+            source,
+            args.dialect,
+            args.emit_ast,
+            args.max_depth,
+            args.max_height,
+            interpreter,
+        );
+    }
+}
+
 fn main() {
     let args = parse_args();
-    match args.script {
-        Some(file) => run_file(&file),
-        None => run_prompt().expect("Error running prompt"),
+    match &args.script {
+        Some(file) => run_file(file, &args),
+        None => run_prompt(&args).expect("Error running prompt"),
     }
 }
@@ -1,81 +1,801 @@
-use crate::expression::expression::Expr;
-use crate::scanner::token::{Token, TokenType};
+use crate::ast::arena::Ast;
+use crate::errors::parse_error::ParseError;
+use crate::expression::expression::{Expr, ExprId};
+use crate::parser::rules::{ParseRules, Precedence};
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::{Literal, Span, Token, TokenType};
+use crate::statement::statement::{Function, Stmt, StmtId};
+use std::rc::Rc;
 
-pub struct Parser {
-    tokens: Vec<Token>,
+/// Upper bound on call arguments and function parameters.
+const MAX_ARGUMENTS: usize = 255;
+
+/// Default for how deeply expressions and statements may nest. Every level is
+/// a few native stack frames here and in the passes that walk the tree, so
+/// this stays far below what overflows the stack.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// Default for how tall an expression tree may grow. The parser loops over a
+/// chain such as `a + b + c`, but the tree it builds is as tall as
+/// `((a + b) + c)`, and the passes that walk it recurse once per level.
+pub const DEFAULT_MAX_HEIGHT: usize = 1024;
+
+pub struct Parser<'t> {
+    tokens: &'t [Token],
+    /// Where the parsed nodes go. Nodes of a statement that fails to parse
+    /// are left behind unreferenced.
+    ast: &'t Ast,
     current: usize,
+    previous: usize,
+    errors: Vec<ParseError>,
+    rules: ParseRules,
+    /// Statements and expressions currently being parsed inside one another.
+    depth: usize,
+    max_depth: usize,
+    /// Height of the tallest expression finished since the one being parsed
+    /// started, which makes it the height of that expression's operands.
+    height: usize,
+    max_height: usize,
+    /// Set when nesting got too deep. Parsing stops there and the input that
+    /// is left over is not looked at.
+    aborted: bool,
 }
 
-impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser {
+impl<'t> Parser<'t> {
+    pub fn new(tokens: &'t [Token], ast: &'t Ast) -> Self {
+        let mut parser = Parser {
             tokens,
+            ast,
             current: 0,
+            previous: 0,
+            errors: Vec::new(),
+            rules: ParseRules::default(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            height: 0,
+            max_height: DEFAULT_MAX_HEIGHT,
+            aborted: false,
+        };
+        parser.skip_trivia();
+        parser
+    }
+
+    /// Limits how deeply statements and expressions may nest, so hostile input
+    /// gets a diagnostic rather than overflowing the stack.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limits how tall an expression tree may grow, which is what long chains
+    /// of operators run into rather than the depth limit.
+    pub fn with_max_height(mut self, max_height: usize) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// Parses expressions with `rules` instead of the standard table.
+    pub fn with_rules(mut self, rules: ParseRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Parses a whole program, reporting every syntax error found rather than
+    /// just the first one.
+    pub fn parse(&mut self) -> Result<Vec<StmtId>, Vec<ParseError>> {
+        let mut statements = Vec::new();
+        while !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
         }
     }
 
-    pub fn parse(&mut self) -> Option<Expr> {
-        self.expression()
+    // declaration -> classDecl | funDecl | varDecl | statement
+    fn declaration(&mut self) -> Option<StmtId> {
+        // Function and class bodies nest through here without passing
+        // `statement`, so this level has to count too.
+        let result = self.nested("Statement nesting too deep.", Self::declaration_body);
+
+        match result {
+            Ok(statement) => Some(statement),
+            // The nesting error is already recorded; the enclosing levels only
+            // fail because their input was cut off.
+            Err(_) if self.aborted => None,
+            Err(error) => {
+                // Skip past the bad tokens and look for more errors after them.
+                self.errors.push(error);
+                self.synchronize();
+                None
+            }
+        }
     }
 
-    fn expression(&mut self) -> Option<Expr> {
-        self.term()
+    fn declaration_body(&mut self) -> Result<StmtId, ParseError> {
+        if self.match_tokens(&[TokenType::Class]) {
+            self.class_declaration()
+        } else if self.match_tokens(&[TokenType::Fun]) {
+            // A statement never starts with a lambda, so `fun` always begins a
+            // declaration here; wrap a lambda in parentheses to call it.
+            let start = self.previous().span;
+            self.function("function")
+                .map(|function| self.add_stmt(start, Stmt::Function(function)))
+        } else if self.match_tokens(&[TokenType::Var]) {
+            self.var_declaration()
+        } else {
+            // Already a level deeper, so skip `statement` and its count.
+            self.statement_body()
+        }
     }
 
-    // term -> factor ( ( "-" | "+" ) factor )*
-    fn term(&mut self) -> Option<Expr> {
-        let mut expr = self.factor()?;
+    // classDecl -> "class" IDENTIFIER ( "<" IDENTIFIER )? "{" function* "}"
+    fn class_declaration(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        let name = self
+            .consume(TokenType::Identifier, "Expect class name.")?
+            .clone();
 
-        while self.match_tokens(&[TokenType::Minus, TokenType::Plus]) {
-            let operator = self.previous().clone();
-            let right = self.factor()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
+        let superclass = if self.match_tokens(&[TokenType::Less]) {
+            let name = self
+                .consume(TokenType::Identifier, "Expect superclass name.")?
+                .clone();
+            Some(self.add_expr(name.span, Expr::Variable { name }))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before class body.")?;
+
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.function("method")?);
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after class body.")?;
+        Ok(self.add_stmt(
+            start,
+            Stmt::Class {
+                name,
+                superclass,
+                methods,
+            },
+        ))
+    }
+
+    // funDecl  -> "fun" function
+    // function -> IDENTIFIER functionBody
+    fn function(&mut self, kind: &str) -> Result<Rc<Function>, ParseError> {
+        let name = self
+            .consume(TokenType::Identifier, &format!("Expect {} name.", kind))?
+            .clone();
+        self.consume(
+            TokenType::LeftParen,
+            &format!("Expect '(' after {} name.", kind),
+        )?;
+        self.function_body(kind, Some(name))
+    }
+
+    // functionBody -> "(" parameters? ")" block
+    // parameters   -> IDENTIFIER ( "," IDENTIFIER )*
+    //
+    // Called with the opening parenthesis already consumed.
+    fn function_body(
+        &mut self,
+        kind: &str,
+        name: Option<Token>,
+    ) -> Result<Rc<Function>, ParseError> {
+        let mut params = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    let error = self.error(&format!(
+                        "Can't have more than {} parameters.",
+                        MAX_ARGUMENTS
+                    ));
+                    self.errors.push(error);
+                }
+                params.push(
+                    self.consume(TokenType::Identifier, "Expect parameter name.")?
+                        .clone(),
+                );
+
+                if !self.match_tokens(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after parameters.")?;
+
+        self.consume(
+            TokenType::LeftBrace,
+            &format!("Expect '{{' before {} body.", kind),
+        )?;
+        let body = self.block()?;
+
+        Ok(Rc::new(Function { name, params, body }))
+    }
+
+    // varDecl -> "var" IDENTIFIER ( "=" expression )? ";"
+    fn var_declaration(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        let name = self
+            .consume(TokenType::Identifier, "Expect variable name.")?
+            .clone();
+
+        let initializer = if self.match_tokens(&[TokenType::Equal]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration.",
+        )?;
+        Ok(self.add_stmt(start, Stmt::Var { name, initializer }))
+    }
+
+    // statement -> exprStmt | forStmt | ifStmt | printStmt | returnStmt
+    //            | whileStmt | breakStmt | continueStmt | block
+    fn statement(&mut self) -> Result<StmtId, ParseError> {
+        self.nested("Statement nesting too deep.", Self::statement_body)
+    }
+
+    fn statement_body(&mut self) -> Result<StmtId, ParseError> {
+        if self.match_tokens(&[TokenType::Break]) {
+            let keyword = self.previous().clone();
+            self.consume(TokenType::Semicolon, "Expect ';' after 'break'.")?;
+            return Ok(self.add_stmt(keyword.span, Stmt::Break { keyword }));
+        }
+        if self.match_tokens(&[TokenType::Continue]) {
+            let keyword = self.previous().clone();
+            self.consume(TokenType::Semicolon, "Expect ';' after 'continue'.")?;
+            return Ok(self.add_stmt(keyword.span, Stmt::Continue { keyword }));
+        }
+        if self.match_tokens(&[TokenType::For]) {
+            return self.for_statement();
+        }
+        if self.match_tokens(&[TokenType::If]) {
+            return self.if_statement();
+        }
+        if self.match_tokens(&[TokenType::While]) {
+            return self.while_statement();
+        }
+        if self.match_tokens(&[TokenType::Print]) {
+            return self.print_statement();
+        }
+        if self.match_tokens(&[TokenType::Return]) {
+            return self.return_statement();
+        }
+        if self.match_tokens(&[TokenType::LeftBrace]) {
+            let start = self.previous().span;
+            let statements = self.block()?;
+            return Ok(self.add_stmt(start, Stmt::Block { statements }));
+        }
+
+        self.expression_statement()
+    }
+
+    // forStmt -> "for" "(" ( varDecl | exprStmt | ";" ) expression? ";" expression? ")" statement
+    //
+    // There is no `for` node: the loop is desugared into
+    // `{ initializer; while (condition) body }`, with the increment kept on
+    // the `while` so that `continue` still runs it.
+    fn for_statement(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'for'.")?;
+
+        let initializer = if self.match_tokens(&[TokenType::Semicolon]) {
+            None
+        } else if self.match_tokens(&[TokenType::Var]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(TokenType::Semicolon, "Expect ';' after loop condition.")?;
+
+        let increment = if self.check(TokenType::RightParen) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(TokenType::RightParen, "Expect ')' after for clauses.")?;
+
+        let body = self.statement()?;
+
+        // A missing condition loops forever.
+        let condition = condition.unwrap_or_else(|| {
+            let value = self.synthetic_true();
+            self.add_expr(value.span, Expr::Literal { value })
+        });
+        let mut body = self.add_stmt(
+            start,
+            Stmt::While {
+                condition,
+                body,
+                increment,
+            },
+        );
+
+        if let Some(initializer) = initializer {
+            body = self.add_stmt(
+                start,
+                Stmt::Block {
+                    statements: vec![initializer, body],
+                },
+            );
+        }
+
+        Ok(body)
+    }
+
+    // ifStmt -> "if" "(" expression ")" statement ( "else" statement )?
+    fn if_statement(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'if'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after if condition.")?;
+
+        // Checking for `else` right away binds it to the nearest `if`, which
+        // settles the dangling-else ambiguity.
+        let then_branch = self.statement()?;
+        let else_branch = if self.match_tokens(&[TokenType::Else]) {
+            Some(self.statement()?)
+        } else {
+            None
+        };
+
+        Ok(self.add_stmt(
+            start,
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+        ))
+    }
+
+    // printStmt -> "print" expression ";"
+    fn print_statement(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        let expression = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after value.")?;
+        Ok(self.add_stmt(start, Stmt::Print { expression }))
+    }
+
+    // returnStmt -> "return" expression? ";"
+    fn return_statement(&mut self) -> Result<StmtId, ParseError> {
+        let keyword = self.previous().clone();
+        let value = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume(TokenType::Semicolon, "Expect ';' after return value.")?;
+        Ok(self.add_stmt(keyword.span, Stmt::Return { keyword, value }))
+    }
+
+    // whileStmt -> "while" "(" expression ")" statement
+    fn while_statement(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after condition.")?;
+        let body = self.statement()?;
+
+        Ok(self.add_stmt(
+            start,
+            Stmt::While {
+                condition,
+                body,
+                increment: None,
+            },
+        ))
+    }
+
+    // exprStmt -> expression ";"
+    fn expression_statement(&mut self) -> Result<StmtId, ParseError> {
+        let expression = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after expression.")?;
+        Ok(self.add_stmt(self.expr_span(expression), Stmt::Expression { expression }))
+    }
+
+    // block -> "{" declaration* "}"
+    fn block(&mut self) -> Result<Vec<StmtId>, ParseError> {
+        let mut statements = Vec::new();
+
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after block.")?;
+        Ok(statements)
+    }
+
+    // expression -> comma
+    //
+    // Every expression is parsed by `parse_precedence`, driven by the table in
+    // `ParseRules`. From loosest to tightest the levels are:
+    //
+    // comma       -> assignment ( "," assignment )*
+    // assignment  -> ( call "." )? IDENTIFIER "=" assignment | conditional
+    // conditional -> logic_or ( "?" expression ":" conditional )?
+    // logic_or    -> logic_and ( "or" logic_and )*
+    // logic_and   -> equality ( "and" equality )*
+    // equality    -> comparison ( ( "!=" | "==" ) comparison )*
+    // comparison  -> term ( ( ">" | ">=" | "<" | "<=" ) term )*
+    // term        -> factor ( ( "-" | "+" ) factor )*
+    // factor      -> unary ( ( "/" | "*" ) unary )*
+    // unary       -> ( "!" | "-" ) unary | call
+    // call        -> primary ( "(" arguments? ")" | "." IDENTIFIER )*
+    // primary     -> NUMBER | STRING | "true" | "false" | "nil" | interpolation
+    //              | "(" expression ")" | IDENTIFIER | "this"
+    //              | "super" "." IDENTIFIER
+    pub fn expression(&mut self) -> Result<ExprId, ParseError> {
+        self.parse_precedence(Precedence::Comma)
+    }
+
+    fn assignment_expression(&mut self) -> Result<ExprId, ParseError> {
+        self.parse_precedence(Precedence::Assignment)
+    }
+
+    /// Parses an expression whose operators all bind at least as tightly as
+    /// `precedence`.
+    pub fn parse_precedence(&mut self, precedence: Precedence) -> Result<ExprId, ParseError> {
+        self.nested("Expression nesting too deep.", |parser| {
+            parser.parse_precedence_body(precedence)
+        })
+    }
+
+    fn parse_precedence_body(&mut self, precedence: Precedence) -> Result<ExprId, ParseError> {
+        // Look before consuming, so a token that can't start an expression is
+        // left for `synchronize` to skip.
+        let Some(prefix) = self.rules.get(self.peek().token_type).prefix else {
+            return Err(self.error("Expect expression."));
+        };
+        self.advance();
+
+        // Operands parsed by the rules raise `height` to the tallest of them,
+        // so once this returns it holds the height of this expression.
+        let enclosing = std::mem::take(&mut self.height);
+        let result = prefix(self).and_then(|left| {
+            self.height += 1;
+            self.infix_operators(left, precedence)
+        });
+        self.height = self.height.max(enclosing);
+        result
+    }
+
+    fn infix_operators(
+        &mut self,
+        mut expr: ExprId,
+        precedence: Precedence,
+    ) -> Result<ExprId, ParseError> {
+        loop {
+            let rule = self.rules.get(self.peek().token_type);
+            let Some(infix) = rule.infix else {
+                break;
             };
+            if rule.precedence < precedence {
+                break;
+            }
+
+            // The parser loops rather than recursing here, but every operator
+            // puts the expression so far one level further down the tree.
+            if self.height >= self.max_height {
+                return Err(self.abort("Expression too long."));
+            }
+
+            self.advance();
+            expr = infix(self, expr)?;
+            self.height += 1;
         }
 
-        Some(expr)
+        Ok(expr)
     }
 
-    // factor -> primary ( ( "/" | "*" ) primary )*
-    fn factor(&mut self) -> Option<Expr> {
-        let mut expr = self.primary()?;
+    /// Precedence of the operator just consumed.
+    pub fn operator_precedence(&self) -> Precedence {
+        self.rules.get(self.previous().token_type).precedence
+    }
+
+    // Infix rules. Like the prefix rules below, they are public so another
+    // table can reuse them for its own tokens.
 
-        while self.match_tokens(&[TokenType::Slash, TokenType::Star]) {
-            let operator = self.previous().clone();
-            let right = self.primary()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+    pub fn binary(&mut self, left: ExprId) -> Result<ExprId, ParseError> {
+        let operator = self.previous().clone();
+        let right = self.parse_precedence(self.operator_precedence().next())?;
+        Ok(self.add_expr(
+            self.expr_span(left),
+            Expr::Binary {
+                left,
                 operator,
-                right: Box::new(right),
-            };
+                right,
+            },
+        ))
+    }
+
+    pub fn logical(&mut self, left: ExprId) -> Result<ExprId, ParseError> {
+        let operator = self.previous().clone();
+        let right = self.parse_precedence(self.operator_precedence().next())?;
+        Ok(self.add_expr(
+            self.expr_span(left),
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            },
+        ))
+    }
+
+    pub fn comma(&mut self, left: ExprId) -> Result<ExprId, ParseError> {
+        let right = self.assignment_expression()?;
+        Ok(self.add_expr(self.expr_span(left), Expr::Comma { left, right }))
+    }
+
+    // The target was parsed as an ordinary expression; a valid one is
+    // replaced by an assignment node and stays in the arena unreferenced.
+    pub fn assignment(&mut self, target: ExprId) -> Result<ExprId, ParseError> {
+        let equals = self.previous().clone();
+        // Same level again, making `=` right-associative.
+        let value = self.assignment_expression()?;
+
+        let assignment = match &*self.ast.expr(target) {
+            Expr::Variable { name } => Some(Expr::Assign {
+                name: name.clone(),
+                value,
+            }),
+            Expr::Get { object, name } => Some(Expr::Set {
+                object: *object,
+                name: name.clone(),
+                value,
+            }),
+            _ => None,
+        };
+
+        match assignment {
+            Some(assignment) => Ok(self.add_expr(self.expr_span(target), assignment)),
+            None => {
+                // Report without unwinding; the parser is not confused, so
+                // there is nothing to synchronize.
+                self.errors
+                    .push(ParseError::new(equals, "Invalid assignment target."));
+                Ok(target)
+            }
         }
+    }
 
-        Some(expr)
+    // The middle operand is delimited by `?` and `:`, so it can be any
+    // expression; the right one is parsed at this same level, making `?:`
+    // right-associative.
+    pub fn conditional(&mut self, condition: ExprId) -> Result<ExprId, ParseError> {
+        let then_branch = self.expression()?;
+        self.consume(
+            TokenType::Colon,
+            "Expect ':' after then branch of conditional expression.",
+        )?;
+        let else_branch = self.parse_precedence(Precedence::Conditional)?;
+        Ok(self.add_expr(
+            self.expr_span(condition),
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            },
+        ))
     }
 
-    // primary -> NUMBER | "(" expression ")"
-    fn primary(&mut self) -> Option<Expr> {
-        if self.match_tokens(&[TokenType::Number]) {
-            return Some(Expr::Literal {
-                value: self.previous().clone(),
-            });
+    // arguments -> assignment ( "," assignment )*
+    //
+    // Arguments start one level below the comma operator, so `f(a, b)` is two
+    // arguments rather than one comma expression.
+    pub fn call(&mut self, callee: ExprId) -> Result<ExprId, ParseError> {
+        let mut arguments = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    // Too many arguments is not a reason to lose our place, so
+                    // report it and keep parsing.
+                    let error = self.error(&format!(
+                        "Can't have more than {} arguments.",
+                        MAX_ARGUMENTS
+                    ));
+                    self.errors.push(error);
+                }
+                arguments.push(self.assignment_expression()?);
+
+                if !self.match_tokens(&[TokenType::Comma]) {
+                    break;
+                }
+            }
         }
 
-        if self.match_tokens(&[TokenType::LeftParen]) {
-            let expr = self.expression()?;
-            self.consume(TokenType::RightParen, "Expect ')' after expression.")?;
-            return Some(expr);
+        let paren = self
+            .consume(TokenType::RightParen, "Expect ')' after arguments.")?
+            .clone();
+
+        Ok(self.add_expr(
+            self.expr_span(callee),
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            },
+        ))
+    }
+
+    pub fn dot(&mut self, object: ExprId) -> Result<ExprId, ParseError> {
+        let name = self
+            .consume(TokenType::Identifier, "Expect property name after '.'.")?
+            .clone();
+        Ok(self.add_expr(self.expr_span(object), Expr::Get { object, name }))
+    }
+
+    // Prefix rules.
+
+    pub fn unary(&mut self) -> Result<ExprId, ParseError> {
+        let operator = self.previous().clone();
+        let right = self.parse_precedence(Precedence::Unary)?;
+        Ok(self.add_expr(operator.span, Expr::Unary { operator, right }))
+    }
+
+    pub fn literal(&mut self) -> Result<ExprId, ParseError> {
+        let value = self.previous().clone();
+        Ok(self.add_expr(value.span, Expr::Literal { value }))
+    }
+
+    // lambda -> "fun" functionBody
+    pub fn lambda(&mut self) -> Result<ExprId, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'fun'.")?;
+        let function = self.function_body("lambda", None)?;
+        Ok(self.add_expr(start, Expr::Function(function)))
+    }
+
+    pub fn grouping(&mut self) -> Result<ExprId, ParseError> {
+        let start = self.previous().span;
+        let expression = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after expression.")?;
+        Ok(self.add_expr(start, Expr::Grouping { expression }))
+    }
+
+    pub fn variable(&mut self) -> Result<ExprId, ParseError> {
+        let name = self.previous().clone();
+        Ok(self.add_expr(name.span, Expr::Variable { name }))
+    }
+
+    pub fn this(&mut self) -> Result<ExprId, ParseError> {
+        let keyword = self.previous().clone();
+        Ok(self.add_expr(keyword.span, Expr::This { keyword }))
+    }
+
+    pub fn super_(&mut self) -> Result<ExprId, ParseError> {
+        let keyword = self.previous().clone();
+        self.consume(TokenType::Dot, "Expect '.' after 'super'.")?;
+        let method = self
+            .consume(TokenType::Identifier, "Expect superclass method name.")?
+            .clone();
+        Ok(self.add_expr(keyword.span, Expr::Super { keyword, method }))
+    }
+
+    // Error production: a binary operator with nothing on its left. The right
+    // operand is parsed at the operator's own precedence and stands in for the
+    // whole expression, so parsing picks up again right after it.
+    pub fn missing_left_operand(&mut self) -> Result<ExprId, ParseError> {
+        let operator = self.previous().clone();
+        // Report before parsing the operand, so errors inside it come after
+        // this one.
+        self.errors.push(ParseError::new(
+            operator.clone(),
+            format!(
+                "Binary operator '{}' is missing a left-hand operand.",
+                operator.lexeme
+            ),
+        ));
+
+        self.parse_precedence(self.operator_precedence().next())
+    }
+
+    // interpolation -> INTERPOLATION expression ( INTERPOLATION expression )* STRING
+    pub fn interpolation(&mut self) -> Result<ExprId, ParseError> {
+        let start = self.previous().span;
+        let mut parts = Vec::new();
+        self.push_segment(&mut parts, self.previous().clone());
+        loop {
+            parts.push(self.expression()?);
+
+            if self.match_tokens(&[TokenType::Interpolation]) {
+                self.push_segment(&mut parts, self.previous().clone());
+            } else {
+                let end = self
+                    .consume(
+                        TokenType::String,
+                        "Expect '}' after interpolated expression.",
+                    )?
+                    .clone();
+                self.push_segment(&mut parts, end);
+                return Ok(self.add_expr(start, Expr::Interpolation { parts }));
+            }
+        }
+    }
+
+    // Empty segments, as on either side of "${x}", add nothing to the result.
+    fn push_segment(&self, parts: &mut Vec<ExprId>, segment: Token) {
+        if !matches!(&segment.literal, Some(Literal::String(s)) if s.as_str().is_empty()) {
+            parts.push(self.add_expr(segment.span, Expr::Literal { value: segment }));
         }
+    }
 
-        None
+    /// Adds a node that runs from `start` through the token just consumed.
+    pub fn add_expr(&self, start: Span, expr: Expr) -> ExprId {
+        self.ast.add_expr(expr, start.to(self.previous().span))
     }
 
-    fn match_tokens(&mut self, types: &[TokenType]) -> bool {
+    fn add_stmt(&self, start: Span, stmt: Stmt) -> StmtId {
+        self.ast.add_stmt(stmt, start.to(self.previous().span))
+    }
+
+    /// Where the already parsed expression `id` is in the source.
+    pub fn expr_span(&self, id: ExprId) -> Span {
+        self.ast.expr_span(id)
+    }
+
+    // Runs `parse` one nesting level deeper, or gives up on the whole parse
+    // with `message` if that is past the limit.
+    fn nested<T>(
+        &mut self,
+        message: &str,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth >= self.max_depth {
+            return Err(self.abort(message));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Records a nesting error and skips to the end of the input.
+    fn abort(&mut self, message: &str) -> ParseError {
+        let error = self.error(message);
+        self.errors.push(error.clone());
+        self.aborted = true;
+        self.current = self.tokens.len() - 1;
+        error
+    }
+
+    // A `true` literal for clauses the source leaves out, placed at the
+    // previous token so diagnostics still point somewhere sensible.
+    fn synthetic_true(&self) -> Token {
+        Token::new(
+            TokenType::True,
+            Symbol::intern("true"),
+            Some(Literal::Bool(true)),
+            self.previous().span,
+        )
+    }
+
+    /// Consumes the current token if it has one of `types`.
+    pub fn match_tokens(&mut self, types: &[TokenType]) -> bool {
         for token_type in types {
-            if self.check(token_type.clone()) {
+            if self.check(*token_type) {
                 self.advance();
                 return true;
             }
@@ -83,16 +803,48 @@ impl Parser {
         false
     }
 
-    fn consume(&mut self, token_type: TokenType, message: &str) -> Option<&Token> {
+    /// Consumes the current token if it is a `token_type`, or fails with
+    /// `message`.
+    pub fn consume(&mut self, token_type: TokenType, message: &str) -> Result<&Token, ParseError> {
         if self.check(token_type) {
-            Some(self.advance())
+            Ok(self.advance())
         } else {
-            println!("Error: {}", message);
-            None
+            Err(self.error(message))
+        }
+    }
+
+    /// An error about the token the parser is currently looking at.
+    pub fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.peek().clone(), message)
+    }
+
+    // Discards tokens until the start of what is probably the next statement.
+    fn synchronize(&mut self) {
+        self.advance();
+
+        while !self.is_at_end() {
+            if self.previous().token_type == TokenType::Semicolon {
+                return;
+            }
+
+            match self.peek().token_type {
+                TokenType::Class
+                | TokenType::Fun
+                | TokenType::Var
+                | TokenType::For
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Print
+                | TokenType::Return => return,
+                _ => {}
+            }
+
+            self.advance();
         }
     }
 
-    fn check(&self, token_type: TokenType) -> bool {
+    /// Whether the current token is a `token_type`, without consuming it.
+    pub fn check(&self, token_type: TokenType) -> bool {
         if self.is_at_end() {
             false
         } else {
@@ -100,22 +852,34 @@ impl Parser {
         }
     }
 
-    fn advance(&mut self) -> &Token {
+    /// Consumes the current token and returns it.
+    pub fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
+            self.previous = self.current;
             self.current += 1;
+            self.skip_trivia();
         }
         self.previous()
     }
 
+    // Doc comments are kept in the token stream for tooling; the grammar ignores them.
+    fn skip_trivia(&mut self) {
+        while self.tokens[self.current].token_type == TokenType::DocComment {
+            self.current += 1;
+        }
+    }
+
     fn is_at_end(&self) -> bool {
         self.peek().token_type == TokenType::Eof
     }
 
-    fn peek(&self) -> &Token {
+    /// The token about to be consumed.
+    pub fn peek(&self) -> &Token {
         &self.tokens[self.current]
     }
 
-    fn previous(&self) -> &Token {
-        &self.tokens[self.current - 1]
+    /// The token just consumed: the operator, for an infix rule.
+    pub fn previous(&self) -> &Token {
+        &self.tokens[self.previous]
     }
-}
\ No newline at end of file
+}
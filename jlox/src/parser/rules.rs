@@ -0,0 +1,186 @@
+use crate::errors::parse_error::ParseError;
+use crate::expression::expression::ExprId;
+use crate::parser::parser::Parser;
+use crate::scanner::token::TokenType;
+use rustc_hash::FxHashMap;
+use std::mem::Discriminant;
+
+/// Binding power of infix operators, loosest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    None,
+    Comma,
+    Assignment,
+    Conditional,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Term,
+    Factor,
+    Unary,
+    Call,
+    Primary,
+}
+
+impl Precedence {
+    /// The level just above this one: where the right operand of a
+    /// left-associative operator at this level is parsed.
+    pub fn next(self) -> Precedence {
+        match self {
+            Precedence::None => Precedence::Comma,
+            Precedence::Comma => Precedence::Assignment,
+            Precedence::Assignment => Precedence::Conditional,
+            Precedence::Conditional => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Term,
+            Precedence::Term => Precedence::Factor,
+            Precedence::Factor => Precedence::Unary,
+            Precedence::Unary => Precedence::Call,
+            Precedence::Call | Precedence::Primary => Precedence::Primary,
+        }
+    }
+}
+
+/// Parses an expression that starts with the token just consumed.
+pub type PrefixFn = fn(&mut Parser<'_>) -> Result<ExprId, ParseError>;
+/// Parses the rest of an expression whose left operand is already parsed and
+/// whose operator token was just consumed.
+pub type InfixFn = fn(&mut Parser<'_>, ExprId) -> Result<ExprId, ParseError>;
+
+/// How one token type behaves at the start of an expression (`prefix`) and
+/// after an operand (`infix`, binding as tightly as `precedence`).
+#[derive(Clone, Copy)]
+pub struct ParseRule {
+    pub prefix: Option<PrefixFn>,
+    pub infix: Option<InfixFn>,
+    pub precedence: Precedence,
+}
+
+impl ParseRule {
+    pub fn new(prefix: Option<PrefixFn>, infix: Option<InfixFn>, precedence: Precedence) -> Self {
+        ParseRule {
+            prefix,
+            infix,
+            precedence,
+        }
+    }
+}
+
+const NO_RULE: ParseRule = ParseRule {
+    prefix: None,
+    infix: None,
+    precedence: Precedence::None,
+};
+
+/// The table that drives expression parsing. Token types without an entry
+/// can neither start nor continue an expression.
+#[derive(Clone)]
+pub struct ParseRules {
+    rules: FxHashMap<Discriminant<TokenType>, ParseRule>,
+}
+
+impl ParseRules {
+    /// Adds or replaces the rule for `token_type`, which is how a dialect adds
+    /// its own operators. A rule builds its node with the `Parser` methods it
+    /// is handed: `previous` for its token, `parse_precedence` for operands,
+    /// and `add_expr` for the result. The standard rules are public too.
+    pub fn register(&mut self, token_type: TokenType, rule: ParseRule) -> &mut Self {
+        self.rules.insert(std::mem::discriminant(&token_type), rule);
+        self
+    }
+
+    pub fn get(&self, token_type: TokenType) -> ParseRule {
+        self.rules
+            .get(&std::mem::discriminant(&token_type))
+            .copied()
+            .unwrap_or(NO_RULE)
+    }
+}
+
+impl Default for ParseRules {
+    /// The rules for standard Lox plus the comma and conditional operators.
+    ///
+    /// Binary operators also get a prefix rule: the error production for an
+    /// operator with no left operand. `-` is the exception, because it is a
+    /// unary operator as well.
+    fn default() -> Self {
+        use Precedence as P;
+        use TokenType as T;
+
+        let mut rules = ParseRules {
+            rules: FxHashMap::default(),
+        };
+        let missing: Option<PrefixFn> = Some(|p| p.missing_left_operand());
+        let binary: Option<InfixFn> = Some(|p, left| p.binary(left));
+        let logical: Option<InfixFn> = Some(|p, left| p.logical(left));
+        let literal: Option<PrefixFn> = Some(|p| p.literal());
+
+        rules
+            .register(
+                T::LeftParen,
+                ParseRule::new(
+                    Some(|p| p.grouping()),
+                    Some(|p, left| p.call(left)),
+                    P::Call,
+                ),
+            )
+            .register(
+                T::Dot,
+                ParseRule::new(None, Some(|p, left| p.dot(left)), P::Call),
+            )
+            .register(
+                T::Comma,
+                ParseRule::new(missing, Some(|p, left| p.comma(left)), P::Comma),
+            )
+            .register(
+                T::Equal,
+                ParseRule::new(None, Some(|p, left| p.assignment(left)), P::Assignment),
+            )
+            .register(
+                T::Question,
+                ParseRule::new(None, Some(|p, left| p.conditional(left)), P::Conditional),
+            )
+            .register(T::Or, ParseRule::new(missing, logical, P::Or))
+            .register(T::And, ParseRule::new(missing, logical, P::And))
+            .register(T::BangEqual, ParseRule::new(missing, binary, P::Equality))
+            .register(T::EqualEqual, ParseRule::new(missing, binary, P::Equality))
+            .register(T::Greater, ParseRule::new(missing, binary, P::Comparison))
+            .register(
+                T::GreaterEqual,
+                ParseRule::new(missing, binary, P::Comparison),
+            )
+            .register(T::Less, ParseRule::new(missing, binary, P::Comparison))
+            .register(T::LessEqual, ParseRule::new(missing, binary, P::Comparison))
+            .register(
+                T::Minus,
+                ParseRule::new(Some(|p| p.unary()), binary, P::Term),
+            )
+            .register(T::Plus, ParseRule::new(missing, binary, P::Term))
+            .register(T::Slash, ParseRule::new(missing, binary, P::Factor))
+            .register(T::Star, ParseRule::new(missing, binary, P::Factor))
+            .register(T::Bang, ParseRule::new(Some(|p| p.unary()), None, P::None))
+            .register(T::Number, ParseRule::new(literal, None, P::None))
+            .register(T::String, ParseRule::new(literal, None, P::None))
+            .register(T::True, ParseRule::new(literal, None, P::None))
+            .register(T::False, ParseRule::new(literal, None, P::None))
+            .register(T::Nil, ParseRule::new(literal, None, P::None))
+            .register(
+                T::Interpolation,
+                ParseRule::new(Some(|p| p.interpolation()), None, P::None),
+            )
+            .register(
+                T::Identifier,
+                ParseRule::new(Some(|p| p.variable()), None, P::None),
+            )
+            .register(T::This, ParseRule::new(Some(|p| p.this()), None, P::None))
+            .register(T::Fun, ParseRule::new(Some(|p| p.lambda()), None, P::None))
+            .register(
+                T::Super,
+                ParseRule::new(Some(|p| p.super_()), None, P::None),
+            );
+        rules
+    }
+}
@@ -0,0 +1,386 @@
+use crate::ast::arena::Ast;
+use crate::errors::resolve_error::ResolveError;
+use crate::expression::expression::{Expr, ExprId, ExprVisitor};
+use crate::interpreter::interpreter::Interpreter;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::Token;
+use crate::statement::statement::{Function, StmtId, StmtVisitor};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// Static pass between parsing and running. It tells the interpreter how many
+/// scopes out each local variable lives, and reports the errors that need no
+/// runtime information, such as `this` outside a class.
+pub struct Resolver<'i> {
+    interpreter: &'i mut Interpreter,
+    /// The interpreter's arena, which the statements being resolved live in.
+    ast: Rc<Ast>,
+    /// One map per block scope, innermost last. A name maps to `false` while
+    /// its initializer is being resolved and `true` once it is ready to use.
+    /// Globals are not tracked.
+    scopes: Vec<FxHashMap<Symbol, bool>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+    /// Loops enclosing the current statement within the current function.
+    loop_depth: usize,
+    errors: Vec<ResolveError>,
+}
+
+impl<'i> Resolver<'i> {
+    pub fn new(interpreter: &'i mut Interpreter) -> Self {
+        Resolver {
+            ast: Rc::clone(interpreter.ast()),
+            interpreter,
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            loop_depth: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Resolves a whole program, reporting every error rather than just the
+    /// first one.
+    pub fn resolve(mut self, statements: &[StmtId]) -> Result<(), Vec<ResolveError>> {
+        self.resolve_stmts(statements);
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn resolve_stmts(&mut self, statements: &[StmtId]) {
+        for &statement in statements {
+            self.resolve_stmt(statement);
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: StmtId) {
+        let ast = Rc::clone(&self.ast);
+        stmt.accept(&ast, self);
+    }
+
+    fn resolve_expr(&mut self, expr: ExprId) {
+        let ast = Rc::clone(&self.ast);
+        expr.accept(&ast, self);
+    }
+
+    fn resolve_function(&mut self, function: &Function, kind: FunctionType) {
+        let enclosing = std::mem::replace(&mut self.current_function, kind);
+        // A loop around the declaration doesn't make `break` legal inside it.
+        let enclosing_loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+
+        self.begin_scope();
+        for param in &function.params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve_stmts(&function.body);
+        self.end_scope();
+
+        self.current_function = enclosing;
+        self.loop_depth = enclosing_loop_depth;
+    }
+
+    fn resolve_local(&mut self, id: ExprId, name: &Token) {
+        if let Some(depth) = self
+            .scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(&name.lexeme))
+        {
+            self.interpreter.resolve(id, depth);
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(FxHashMap::default());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+        if scope.insert(name.lexeme, false).is_some() {
+            self.error(name, "Already a variable with this name in this scope.");
+        }
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme, true);
+        }
+    }
+
+    // Binds a name that has no token in the source, like `this` and `super`.
+    fn define_implicit(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(Symbol::intern(name), true);
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        self.errors.push(ResolveError::new(token, message));
+    }
+}
+
+impl ExprVisitor<()> for Resolver<'_> {
+    fn visit_assign_expr(&mut self, id: ExprId, name: &Token, value: ExprId) {
+        self.resolve_expr(value);
+        self.resolve_local(id, name);
+    }
+
+    fn visit_binary_expr(&mut self, _id: ExprId, left: ExprId, _operator: &Token, right: ExprId) {
+        self.resolve_expr(left);
+        self.resolve_expr(right);
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        _id: ExprId,
+        callee: ExprId,
+        _paren: &Token,
+        arguments: &[ExprId],
+    ) {
+        self.resolve_expr(callee);
+        for &argument in arguments {
+            self.resolve_expr(argument);
+        }
+    }
+
+    fn visit_comma_expr(&mut self, _id: ExprId, left: ExprId, right: ExprId) {
+        self.resolve_expr(left);
+        self.resolve_expr(right);
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        _id: ExprId,
+        condition: ExprId,
+        then_branch: ExprId,
+        else_branch: ExprId,
+    ) {
+        self.resolve_expr(condition);
+        self.resolve_expr(then_branch);
+        self.resolve_expr(else_branch);
+    }
+
+    fn visit_function_expr(&mut self, _id: ExprId, function: &Rc<Function>) {
+        self.resolve_function(function, FunctionType::Function);
+    }
+
+    fn visit_get_expr(&mut self, _id: ExprId, object: ExprId, _name: &Token) {
+        // Properties are looked up dynamically, so only the object resolves.
+        self.resolve_expr(object);
+    }
+
+    fn visit_grouping_expr(&mut self, _id: ExprId, expression: ExprId) {
+        self.resolve_expr(expression);
+    }
+
+    fn visit_literal_expr(&mut self, _id: ExprId, _value: &Token) {}
+
+    fn visit_logical_expr(&mut self, _id: ExprId, left: ExprId, _operator: &Token, right: ExprId) {
+        self.resolve_expr(left);
+        self.resolve_expr(right);
+    }
+
+    fn visit_set_expr(&mut self, _id: ExprId, object: ExprId, _name: &Token, value: ExprId) {
+        self.resolve_expr(value);
+        self.resolve_expr(object);
+    }
+
+    fn visit_super_expr(&mut self, id: ExprId, keyword: &Token, _method: &Token) {
+        match self.current_class {
+            ClassType::None => self.error(keyword, "Can't use 'super' outside of a class."),
+            ClassType::Class => {
+                self.error(keyword, "Can't use 'super' in a class with no superclass.")
+            }
+            ClassType::Subclass => {}
+        }
+        self.resolve_local(id, keyword);
+    }
+
+    fn visit_this_expr(&mut self, id: ExprId, keyword: &Token) {
+        if self.current_class == ClassType::None {
+            self.error(keyword, "Can't use 'this' outside of a class.");
+            return;
+        }
+        self.resolve_local(id, keyword);
+    }
+
+    fn visit_unary_expr(&mut self, _id: ExprId, _operator: &Token, right: ExprId) {
+        self.resolve_expr(right);
+    }
+
+    fn visit_variable_expr(&mut self, id: ExprId, name: &Token) {
+        if self.scopes.last().and_then(|scope| scope.get(&name.lexeme)) == Some(&false) {
+            self.error(name, "Can't read local variable in its own initializer.");
+        }
+        self.resolve_local(id, name);
+    }
+
+    fn visit_interpolation_expr(&mut self, _id: ExprId, parts: &[ExprId]) {
+        for &part in parts {
+            self.resolve_expr(part);
+        }
+    }
+}
+
+impl StmtVisitor<()> for Resolver<'_> {
+    fn visit_block_stmt(&mut self, _id: StmtId, statements: &[StmtId]) {
+        self.begin_scope();
+        self.resolve_stmts(statements);
+        self.end_scope();
+    }
+
+    fn visit_break_stmt(&mut self, _id: StmtId, keyword: &Token) {
+        if self.loop_depth == 0 {
+            self.error(keyword, "Can't use 'break' outside of a loop.");
+        }
+    }
+
+    fn visit_class_stmt(
+        &mut self,
+        _id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) {
+        let enclosing_class = std::mem::replace(&mut self.current_class, ClassType::Class);
+
+        self.declare(name);
+        self.define(name);
+
+        if let Some(superclass) = superclass {
+            let ast = Rc::clone(&self.ast);
+            if let Expr::Variable { name: super_name } = &*ast.expr(superclass) {
+                if super_name.lexeme == name.lexeme {
+                    self.error(super_name, "A class can't inherit from itself.");
+                }
+            }
+            self.current_class = ClassType::Subclass;
+            self.resolve_expr(superclass);
+
+            self.begin_scope();
+            self.define_implicit("super");
+        }
+
+        self.begin_scope();
+        self.define_implicit("this");
+
+        for method in methods {
+            let is_init = method
+                .name
+                .as_ref()
+                .is_some_and(|name| name.lexeme.as_str() == "init");
+            let kind = if is_init {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(method, kind);
+        }
+
+        self.end_scope();
+        if superclass.is_some() {
+            self.end_scope();
+        }
+
+        self.current_class = enclosing_class;
+    }
+
+    fn visit_continue_stmt(&mut self, _id: StmtId, keyword: &Token) {
+        if self.loop_depth == 0 {
+            self.error(keyword, "Can't use 'continue' outside of a loop.");
+        }
+    }
+
+    fn visit_expression_stmt(&mut self, _id: StmtId, expression: ExprId) {
+        self.resolve_expr(expression);
+    }
+
+    fn visit_function_stmt(&mut self, _id: StmtId, function: &Rc<Function>) {
+        // Define the name first so the function can refer to itself.
+        if let Some(name) = &function.name {
+            self.declare(name);
+            self.define(name);
+        }
+        self.resolve_function(function, FunctionType::Function);
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) {
+        self.resolve_expr(condition);
+        self.resolve_stmt(then_branch);
+        if let Some(else_branch) = else_branch {
+            self.resolve_stmt(else_branch);
+        }
+    }
+
+    fn visit_print_stmt(&mut self, _id: StmtId, expression: ExprId) {
+        self.resolve_expr(expression);
+    }
+
+    fn visit_return_stmt(&mut self, _id: StmtId, keyword: &Token, value: Option<ExprId>) {
+        if self.current_function == FunctionType::None {
+            self.error(keyword, "Can't return from top-level code.");
+        }
+
+        if let Some(value) = value {
+            if self.current_function == FunctionType::Initializer {
+                self.error(keyword, "Can't return a value from an initializer.");
+            }
+            self.resolve_expr(value);
+        }
+    }
+
+    fn visit_var_stmt(&mut self, _id: StmtId, name: &Token, initializer: Option<ExprId>) {
+        self.declare(name);
+        if let Some(initializer) = initializer {
+            self.resolve_expr(initializer);
+        }
+        self.define(name);
+    }
+
+    fn visit_while_stmt(
+        &mut self,
+        _id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) {
+        self.resolve_expr(condition);
+        self.loop_depth += 1;
+        self.resolve_stmt(body);
+        self.loop_depth -= 1;
+        if let Some(increment) = increment {
+            self.resolve_expr(increment);
+        }
+    }
+}
@@ -1,10 +1,37 @@
-use crate::ast::ast_printer::AstPrinter;
-use crate::parser::parser::Parser;
-use crate::scanner::token::{Token, TokenType};
+use crate::dialect::dialect::Dialect;
+use crate::errors::scan_error::ScanError;
+use crate::scanner::symbol::Symbol;
+use crate::scanner::token::{Literal, Span, Token, TokenType};
+use crate::scanner::trivia::{TokenTrivia, Trivia, TriviaKind};
 use std::cmp::PartialEq;
+use unicode_normalization::{is_nfc, is_nfc_quick, IsNormalized, UnicodeNormalization};
+use unicode_xid::UnicodeXID;
 
-pub struct Scanner {
-    source: String,
+/// Scans a borrowed source by byte index. The scanner is itself an iterator that
+/// lazily yields one token (or lexical error) at a time, ending with `Eof`.
+pub struct Scanner<'src> {
+    source: &'src str,
+    dialect: Dialect,
+    keep_trivia: bool,
+    /// Leading trivia collected for the next token in lossless mode.
+    pending_trivia: Vec<Trivia>,
+    /// One entry per open `${`, counting the `{` nested inside it that are
+    /// still open, so the scanner knows which `}` resumes the string.
+    interpolations: Vec<usize>,
+    start: usize,
+    current: usize,
+    line: usize,
+    column: usize,
+    start_line: usize,
+    start_column: usize,
+    finished: bool,
+}
+
+/// Everything a scan produced: the tokens (always terminated by `Eof`) and every
+/// lexical error found along the way.
+pub struct ScanResult {
+    pub tokens: Vec<Token>,
+    pub errors: Vec<ScanError>,
 }
 
 impl PartialEq for TokenType {
@@ -13,234 +40,646 @@ impl PartialEq for TokenType {
     }
 }
 
-impl Scanner {
-    pub fn new(source: String) -> Self {
-        Scanner { source }
+impl<'src> Scanner<'src> {
+    pub fn new(source: &'src str) -> Self {
+        Scanner {
+            source,
+            dialect: Dialect::Strict,
+            keep_trivia: false,
+            pending_trivia: Vec::new(),
+            interpolations: Vec::new(),
+            start: 0,
+            current: 0,
+            line: 1,
+            column: 1,
+            start_line: 1,
+            start_column: 1,
+            finished: false,
+        }
     }
 
-    pub fn scan_tokens(&self) -> Vec<Token> {
-        let mut tokens: Vec<Token> = Vec::new();
-        let mut chars = self.source.chars().peekable();
-        let mut line = 1;
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
 
-        while let Some(&_c) = chars.peek() {
-            match self.scan_token(&mut chars, &mut line) {
-                Ok(Some(token)) => {
-                    if token.token_type != TokenType::Eof {
-                        tokens.push(token);
-                    }
-                }
-                Ok(None) => {}
-                Err(e) => {
-                    crate::errors::generic_error::error(line, &e);
-                    break;
-                }
+    /// Switches on lossless mode, in which every token carries its leading and
+    /// trailing trivia so that `trivia::to_source` can rebuild the source.
+    pub fn with_trivia(mut self, keep_trivia: bool) -> Self {
+        self.keep_trivia = keep_trivia;
+        self
+    }
+
+    pub fn scan_tokens(self) -> ScanResult {
+        let mut tokens: Vec<Token> = Vec::with_capacity(self.source.len() / 4);
+        let mut errors: Vec<ScanError> = Vec::new();
+
+        // scan_token always consumes the text it reports an error for, so the
+        // scan simply carries on with whatever follows.
+        for result in self {
+            match result {
+                Ok(token) => tokens.push(token),
+                Err(e) => errors.push(e),
             }
-            chars.next(); // Consume the character
-        }
-
-        tokens.push(Token::new(TokenType::Eof, "".to_string(), None, line));
-        tokens
-    }
-
-    pub fn scan_token(
-        &self,
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        line: &mut usize,
-    ) -> Result<Option<Token>, String> {
-        while let Some(&c) = chars.peek() {
-            match c {
-                '(' => return Ok(Some(self.make_token(TokenType::LeftParen, chars, line))),
-                ')' => return Ok(Some(self.make_token(TokenType::RightParen, chars, line))),
-                '{' => return Ok(Some(self.make_token(TokenType::LeftBrace, chars, line))),
-                '}' => return Ok(Some(self.make_token(TokenType::RightBrace, chars, line))),
-                ',' => return Ok(Some(self.make_token(TokenType::Comma, chars, line))),
-                '.' => return Ok(Some(self.make_token(TokenType::Dot, chars, line))),
-                '-' => return Ok(Some(self.make_token(TokenType::Minus, chars, line))),
-                '+' => return Ok(Some(self.make_token(TokenType::Plus, chars, line))),
-                ';' => return Ok(Some(self.make_token(TokenType::Semicolon, chars, line))),
-                '*' => return Ok(Some(self.make_token(TokenType::Star, chars, line))),
-                '!' => {
-                    return Ok(Some(self.match_token(
-                        '=',
-                        TokenType::BangEqual,
-                        TokenType::Bang,
-                        chars,
-                        line,
-                    )))
+        }
+
+        ScanResult { tokens, errors }
+    }
+
+    pub fn scan_token(&mut self) -> Result<Token, ScanError> {
+        let result = self.scan_significant_token();
+        if !self.keep_trivia {
+            return result;
+        }
+
+        match result {
+            Ok(mut token) => {
+                let trivia = TokenTrivia {
+                    leading: std::mem::take(&mut self.pending_trivia),
+                    trailing: self.trailing_trivia(),
+                };
+                token.trivia = Some(Box::new(trivia));
+                Ok(token)
+            }
+            Err(e) => {
+                // Whatever the failed token consumed still has to round-trip.
+                let skipped = self.trivia(
+                    TriviaKind::Skipped,
+                    self.start,
+                    self.start_line,
+                    self.start_column,
+                );
+                self.pending_trivia.push(skipped);
+                Err(e)
+            }
+        }
+    }
+
+    fn scan_significant_token(&mut self) -> Result<Token, ScanError> {
+        self.skip_whitespace()?;
+        self.begin_token();
+
+        let Some(c) = self.advance() else {
+            if !self.interpolations.is_empty() {
+                self.interpolations.clear();
+                return Err(ScanError::new(
+                    self.span(),
+                    "Unterminated string interpolation.",
+                ));
+            }
+            self.finished = true;
+            return Ok(self.make_token(TokenType::Eof, None));
+        };
+
+        match c {
+            '(' => Ok(self.make_token(TokenType::LeftParen, None)),
+            ')' => Ok(self.make_token(TokenType::RightParen, None)),
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
                 }
-                '=' => {
-                    return Ok(Some(self.match_token(
-                        '=',
-                        TokenType::EqualEqual,
-                        TokenType::Equal,
-                        chars,
-                        line,
-                    )))
+                Ok(self.make_token(TokenType::LeftBrace, None))
+            }
+            '}' => match self.interpolations.last_mut() {
+                // This brace closes a `${`, so the string picks up again.
+                Some(0) => {
+                    self.interpolations.pop();
+                    self.string()
                 }
-                '<' => {
-                    return Ok(Some(self.match_token(
-                        '=',
-                        TokenType::LessEqual,
-                        TokenType::Less,
-                        chars,
-                        line,
-                    )))
+                Some(depth) => {
+                    *depth -= 1;
+                    Ok(self.make_token(TokenType::RightBrace, None))
                 }
-                '>' => {
-                    return Ok(Some(self.match_token(
-                        '=',
-                        TokenType::GreaterEqual,
-                        TokenType::Greater,
-                        chars,
-                        line,
-                    )))
+                None => Ok(self.make_token(TokenType::RightBrace, None)),
+            },
+            ',' => Ok(self.make_token(TokenType::Comma, None)),
+            '.' => Ok(self.make_token(TokenType::Dot, None)),
+            '-' => Ok(self.make_token(TokenType::Minus, None)),
+            '+' => Ok(self.make_token(TokenType::Plus, None)),
+            ';' => Ok(self.make_token(TokenType::Semicolon, None)),
+            '*' => Ok(self.make_token(TokenType::Star, None)),
+            '?' => Ok(self.make_token(TokenType::Question, None)),
+            ':' => Ok(self.make_token(TokenType::Colon, None)),
+            // skip_whitespace only leaves a "//" in place when it opens a doc comment.
+            '/' if self.peek() == Some('/') => Ok(self.doc_comment()),
+            '/' => Ok(self.make_token(TokenType::Slash, None)),
+            '!' => Ok(self.match_token('=', TokenType::BangEqual, TokenType::Bang)),
+            '=' => Ok(self.match_token('=', TokenType::EqualEqual, TokenType::Equal)),
+            '<' => Ok(self.match_token('=', TokenType::LessEqual, TokenType::Less)),
+            '>' => Ok(self.match_token('=', TokenType::GreaterEqual, TokenType::Greater)),
+            '"' => self.string(),
+            c if is_identifier_start(c) => Ok(self.identifier()),
+            c if c.is_ascii_digit() => self.number(),
+            c => Err(ScanError::new(
+                self.span(),
+                format!("Unexpected character: {}", c),
+            )),
+        }
+    }
+
+    fn begin_token(&mut self) {
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), ScanError> {
+        loop {
+            let (start, line, column) = (self.current, self.line, self.column);
+            match self.skip_trivia_piece()? {
+                Some(kind) if self.keep_trivia => {
+                    let piece = self.trivia(kind, start, line, column);
+                    self.pending_trivia.push(piece);
                 }
-                '/' => {
-                    chars.next();
-                    if let Some('/') = chars.peek() {
-                        while let Some(&c) = chars.peek() {
-                            if c == '\n' {
-                                break;
-                            }
-                            chars.next();
-                        }
-                    } else {
-                        return Ok(Some(Token::new(
-                            TokenType::Slash,
-                            "/".to_string(),
-                            None,
-                            *line,
-                        )));
-                    }
+                Some(_) => {}
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Consumes one piece of whitespace or comment, if the scanner is at one,
+    /// and says what kind it was.
+    fn skip_trivia_piece(&mut self) -> Result<Option<TriviaKind>, ScanError> {
+        let kind = match self.peek() {
+            Some(' ' | '\r' | '\t') => {
+                while matches!(self.peek(), Some(' ' | '\r' | '\t')) {
+                    self.advance();
                 }
-                '\n' => {
-                    *line += 1;
-                    chars.next();
+                TriviaKind::Whitespace
+            }
+            Some('\n') => {
+                self.advance();
+                TriviaKind::Newline
+            }
+            Some('/') if self.at_doc_comment() => return Ok(None),
+            Some('/') if self.peek_next() == Some('/') => {
+                self.skip_line();
+                TriviaKind::LineComment
+            }
+            Some('/') if self.peek_next() == Some('*') => {
+                self.block_comment()?;
+                TriviaKind::BlockComment
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(kind))
+    }
+
+    // Trailing trivia ends with the line; a block comment is left to lead the
+    // next token, since it may well span several lines.
+    fn trailing_trivia(&mut self) -> Vec<Trivia> {
+        let mut trailing = Vec::new();
+        while !self.at("/*") {
+            let (start, line, column) = (self.current, self.line, self.column);
+            let Ok(Some(kind)) = self.skip_trivia_piece() else {
+                break;
+            };
+            trailing.push(self.trivia(kind, start, line, column));
+            if kind == TriviaKind::Newline {
+                break;
+            }
+        }
+        trailing
+    }
+
+    fn trivia(&self, kind: TriviaKind, start: usize, line: usize, column: usize) -> Trivia {
+        Trivia {
+            kind,
+            span: Span::new(start, self.current, line, column),
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.advance();
+        }
+    }
+
+    // Exactly three slashes; "////" and longer are ordinary comments.
+    fn at_doc_comment(&self) -> bool {
+        let rest = &self.source[self.current..];
+        rest.starts_with("///") && !rest.starts_with("////")
+    }
+
+    fn doc_comment(&mut self) -> Token {
+        self.skip_line();
+        self.make_token(TokenType::DocComment, None)
+    }
+
+    // Block comments nest, so "/* a /* b */ c */" is a single comment.
+    fn block_comment(&mut self) -> Result<(), ScanError> {
+        self.begin_token();
+        self.advance();
+        self.advance();
+
+        let mut depth = 1;
+        while depth > 0 {
+            if self.at("/*") {
+                self.advance();
+                self.advance();
+                depth += 1;
+            } else if self.at("*/") {
+                self.advance();
+                self.advance();
+                depth -= 1;
+            } else if self.advance().is_none() {
+                return Err(ScanError::new(self.span(), "Unterminated block comment."));
+            }
+        }
+        Ok(())
+    }
+
+    fn at(&self, text: &str) -> bool {
+        self.source[self.current..].starts_with(text)
+    }
+
+    fn peek(&self) -> Option<char> {
+        // Most source is ASCII, which can be read straight from the byte.
+        match self.source.as_bytes().get(self.current) {
+            Some(&b) if b.is_ascii() => Some(b as char),
+            Some(_) => self.source[self.current..].chars().next(),
+            None => None,
+        }
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.source[self.current..].chars().nth(1)
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.current += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Source text of the token being scanned.
+    fn lexeme(&self) -> &'src str {
+        &self.source[self.start..self.current]
+    }
+
+    fn span(&self) -> Span {
+        Span::new(self.start, self.current, self.start_line, self.start_column)
+    }
+
+    fn make_token(&self, token_type: TokenType, literal: Option<Literal>) -> Token {
+        Token::new(
+            token_type,
+            Symbol::intern(self.lexeme()),
+            literal,
+            self.span(),
+        )
+    }
+
+    fn match_token(&mut self, expected: char, if_match: TokenType, if_not: TokenType) -> Token {
+        if self.match_char(expected) {
+            self.make_token(if_match, None)
+        } else {
+            self.make_token(if_not, None)
+        }
+    }
+
+    fn identifier(&mut self) -> Token {
+        while self.peek().is_some_and(is_identifier_continue) {
+            self.advance();
+        }
+        let token_type = keyword(self.lexeme()).unwrap_or(TokenType::Identifier);
+        let literal = match token_type {
+            TokenType::False => Some(Literal::Bool(false)),
+            TokenType::True => Some(Literal::Bool(true)),
+            TokenType::Nil => Some(Literal::Nil),
+            _ => None,
+        };
+        if token_type != TokenType::Identifier {
+            return self.make_token(token_type, literal);
+        }
+
+        // Identifiers are NFC-normalized so that canonically equivalent
+        // spellings name the same variable; keywords above matched exactly.
+        let name = self.lexeme();
+        let lexeme = match is_nfc_quick(name.chars()) {
+            IsNormalized::Yes => Symbol::intern(name),
+            _ => Symbol::intern(&name.nfc().collect::<String>()),
+        };
+        Token::new(token_type, lexeme, literal, self.span())
+    }
+
+    // string -> ( "\"" | "}" ) ( CHAR | escape )* ( "\"" | "${" )
+    //
+    // A string that reaches `${` is returned as an Interpolation segment; the
+    // scanner comes back here when the matching `}` shows up.
+    fn string(&mut self) -> Result<Token, ScanError> {
+        let mut value = String::new();
+        let mut error = None;
+        let token_type = loop {
+            match self.peek() {
+                None => return Err(ScanError::new(self.span(), "Unterminated string.")),
+                Some('"') => {
+                    self.advance();
+                    break TokenType::String;
                 }
-                ' ' | '\r' | '\t' => {
-                    chars.next();
+                Some('$') if self.peek_next() == Some('{') => {
+                    self.advance();
+                    self.advance();
+                    self.interpolations.push(0);
+                    break TokenType::Interpolation;
                 }
-                '"' => return Ok(Some(self.string(chars, line))),
-                _ => {
-                    return if c.is_alphabetic() {
-                        Ok(Some(self.identifier(chars, line)))
-                    } else if c.is_ascii_digit() {
-                        Ok(Some(self.number(chars, line)))
-                    } else {
-                        Err(format!("Unexpected character: {}", c))
+                Some('\\') => {
+                    let escape = Span::new(self.current, self.current, self.line, self.column);
+                    self.advance();
+                    match self.escape() {
+                        Ok(c) => value.push(c),
+                        Err(message) => {
+                            // Keep scanning to the closing quote so the rest of the
+                            // string is not mistaken for code.
+                            let span = Span {
+                                end: self.current,
+                                ..escape
+                            };
+                            error.get_or_insert(ScanError::new(span, message));
+                        }
                     }
                 }
+                Some(c) => {
+                    self.advance();
+                    value.push(c);
+                }
             }
+        };
+        if let Some(error) = error {
+            return Err(error);
         }
-        Ok(None)
+
+        let value = Symbol::intern(&value);
+        Ok(self.make_token(token_type, Some(Literal::String(value))))
     }
 
-    fn match_token(
-        &self,
-        expected: char,
-        if_match: TokenType,
-        if_not: TokenType,
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        line: &mut usize,
-    ) -> Token {
-        let mut lexeme = chars.next().unwrap().to_string();
-        if let Some(&next) = chars.peek() {
-            if next == expected {
-                lexeme.push(chars.next().unwrap());
-                return Token::new(if_match, lexeme, None, *line);
-            }
+    // escape -> "\\" ( "n" | "t" | "\"" | "\\" | "$" | "u{" HEX_DIGIT{1,6} "}" )
+    fn escape(&mut self) -> Result<char, String> {
+        match self.advance() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('$') => Ok('$'),
+            Some('u') => self.unicode_escape(),
+            Some(c) => Err(format!("Unknown escape sequence '\\{}'.", c)),
+            None => Err("Unterminated string.".to_string()),
         }
-        Token::new(if_not, lexeme, None, *line)
     }
 
-    fn make_token(
-        &self,
-        token_type: TokenType,
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        line: &mut usize,
-    ) -> Token {
-        let lexeme = chars.next().unwrap().to_string();
-        Token::new(token_type, lexeme, None, *line)
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        if !self.match_char('{') {
+            return Err("Expect '{' after '\\u'.".to_string());
+        }
+
+        let digits_start = self.current;
+        while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            self.advance();
+        }
+        let hex = &self.source[digits_start..self.current];
+        if !self.match_char('}') {
+            return Err("Expect '}' after unicode escape digits.".to_string());
+        }
+
+        if hex.is_empty() || hex.len() > 6 {
+            return Err("Unicode escape must have 1 to 6 hex digits.".to_string());
+        }
+        u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("'\\u{{{}}}' is not a valid Unicode scalar value.", hex))
     }
 
-    fn identifier(
-        &self,
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        line: &mut usize,
-    ) -> Token {
-        let mut lexeme = String::new();
-        while let Some(&c) = chars.peek() {
-            if c.is_alphanumeric() {
-                lexeme.push(chars.next().unwrap());
-            } else {
+    // number -> DIGIT+ ( "." DIGIT+ )?
+    fn number(&mut self) -> Result<Token, ScanError> {
+        if self.dialect == Dialect::Extended {
+            return self.extended_number();
+        }
+
+        self.digits();
+
+        // Look for a fractional part; a trailing '.' is left for the Dot token.
+        if self.peek() == Some('.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+            self.digits();
+        }
+
+        if let Some(form) = self.extended_number_form() {
+            self.skip_number_tail();
+            return Err(ScanError::new(
+                self.span(),
+                format!("{} require the extended dialect.", form),
+            ));
+        }
+
+        let value = self
+            .lexeme()
+            .parse::<f64>()
+            .expect("scanned number is a valid f64");
+        Ok(self.make_token(TokenType::Number, Some(Literal::Number(value))))
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+        }
+    }
+
+    /// Names the extended number syntax that continues the strict number just
+    /// scanned, if there is any.
+    fn extended_number_form(&self) -> Option<&'static str> {
+        let after_zero = self.lexeme() == "0";
+        match (self.peek()?, self.peek_next()) {
+            ('x' | 'X', _) if after_zero => Some("Hexadecimal literals"),
+            ('b' | 'B', _) if after_zero => Some("Binary literals"),
+            ('o' | 'O', _) if after_zero => Some("Octal literals"),
+            ('_', _) => Some("Digit separators"),
+            ('e' | 'E', Some(c)) if c.is_ascii_digit() || c == '+' || c == '-' => Some("Exponents"),
+            _ => None,
+        }
+    }
+
+    /// Consumes whatever is left of a malformed number so that it is reported
+    /// once instead of turning into a run of unrelated tokens.
+    fn skip_number_tail(&mut self) {
+        let mut previous = ' ';
+        while let Some(c) = self.peek() {
+            let exponent_sign = matches!(c, '+' | '-') && matches!(previous, 'e' | 'E');
+            let fraction = c == '.' && self.peek_next().is_some_and(|c| c.is_ascii_digit());
+            if !(is_identifier_continue(c) || exponent_sign || fraction) {
                 break;
             }
+            previous = c;
+            self.advance();
         }
-        let token_type = match lexeme.as_str() {
-            "and" => TokenType::And,
-            "class" => TokenType::Class,
-            "else" => TokenType::Else,
-            "false" => TokenType::False,
-            "for" => TokenType::For,
-            "fun" => TokenType::Fun,
-            "if" => TokenType::If,
-            "nil" => TokenType::Nil,
-            "or" => TokenType::Or,
-            "print" => TokenType::Print,
-            "return" => TokenType::Return,
-            "super" => TokenType::Super,
-            "this" => TokenType::This,
-            "true" => TokenType::True,
-            "var" => TokenType::Var,
-            "while" => TokenType::While,
-            _ => TokenType::Identifier,
-        };
-        Token::new(token_type, lexeme, None, *line)
     }
 
-    fn string(&self, chars: &mut std::iter::Peekable<std::str::Chars>, line: &mut usize) -> Token {
-        let mut lexeme = String::new();
-        chars.next(); // Consume the opening quote
-        while let Some(&c) = chars.peek() {
-            if c == '"' {
-                break;
+    fn extended_number(&mut self) -> Result<Token, ScanError> {
+        match self.extended_number_value() {
+            Ok(value) => Ok(self.make_token(TokenType::Number, Some(Literal::Number(value)))),
+            Err(message) => {
+                self.skip_number_tail();
+                Err(ScanError::new(self.span(), message))
             }
-            if c == '\n' {
-                *line += 1;
+        }
+    }
+
+    // number -> "0" ( "x" | "X" ) DIGITS<16>
+    //         | "0" ( "b" | "B" ) DIGITS<2>
+    //         | "0" ( "o" | "O" ) DIGITS<8>
+    //         | DIGITS<10> ( "." DIGITS<10> )? ( ( "e" | "E" ) ( "+" | "-" )? DIGITS<10> )?
+    // DIGITS<r> -> DIGIT<r> ( "_"? DIGIT<r> )*
+    fn extended_number_value(&mut self) -> Result<f64, String> {
+        // The first digit has already been consumed.
+        let mut text = self.lexeme().to_string();
+
+        if text == "0" {
+            let prefix = self.peek().unwrap_or(' ');
+            let radix = match prefix {
+                'x' | 'X' => Some((16, "hexadecimal")),
+                'b' | 'B' => Some((2, "binary")),
+                'o' | 'O' => Some((8, "octal")),
+                _ => None,
+            };
+            if let Some((radix, name)) = radix {
+                self.advance();
+                text.clear();
+                if self.separated_digits(radix, false, &mut text)? == 0 {
+                    return Err(format!("Expect {} digits after '0{}'.", name, prefix));
+                }
+                if let Some(c) = self.peek().filter(|&c| is_identifier_continue(c)) {
+                    return Err(format!("Invalid digit '{}' in {} literal.", c, name));
+                }
+                return u64::from_str_radix(&text, radix)
+                    .map(|value| value as f64)
+                    .map_err(|_| format!("The {} literal is too large.", name));
             }
-            lexeme.push(chars.next().unwrap());
         }
-        chars.next(); // Consume the closing quote
-        Token::new(TokenType::String, lexeme, None, *line)
+
+        self.separated_digits(10, true, &mut text)?;
+
+        if self.peek() == Some('.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+            text.push('.');
+            self.separated_digits(10, false, &mut text)?;
+        }
+
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.advance();
+            text.push('e');
+            if let Some(sign) = self.peek().filter(|&c| c == '+' || c == '-') {
+                self.advance();
+                text.push(sign);
+            }
+            if self.separated_digits(10, false, &mut text)? == 0 {
+                return Err("Expect digits in exponent.".to_string());
+            }
+        }
+
+        Ok(text.parse::<f64>().expect("scanned number is a valid f64"))
     }
 
-    fn number(&self, chars: &mut std::iter::Peekable<std::str::Chars>, line: &mut usize) -> Token {
-        let mut lexeme = String::new();
-        while let Some(&c) = chars.peek() {
-            if c.is_ascii_digit() {
-                lexeme.push(chars.next().unwrap());
+    /// Consumes digits of the given radix and `_` separators, appending just the
+    /// digits to `text`. Returns how many digits were read. `after_digit` says
+    /// whether a digit directly precedes the run, which allows a leading `_`.
+    fn separated_digits(
+        &mut self,
+        radix: u32,
+        after_digit: bool,
+        text: &mut String,
+    ) -> Result<usize, String> {
+        let mut count = 0;
+        let mut previous_digit = after_digit;
+        let mut misplaced_separator = false;
+        while let Some(c) = self.peek() {
+            if c == '_' {
+                misplaced_separator |= !previous_digit;
+                previous_digit = false;
+            } else if c.is_digit(radix) {
+                text.push(c);
+                count += 1;
+                previous_digit = true;
             } else {
                 break;
             }
+            self.advance();
+        }
+
+        if misplaced_separator || !previous_digit && (count > 0 || after_digit) {
+            return Err("Digit separator '_' must be between digits.".to_string());
         }
-        Token::new(TokenType::Number, lexeme, None, *line)
+        Ok(count)
     }
 }
 
-pub fn run(source: String) {
-    let scanner = Scanner::new(source);
-    let tokens = scanner.scan_tokens();
+/// The reserved word spelled `text`, if it is one.
+pub fn keyword(text: &str) -> Option<TokenType> {
+    let token_type = match text {
+        "and" => TokenType::And,
+        "break" => TokenType::Break,
+        "class" => TokenType::Class,
+        "continue" => TokenType::Continue,
+        "else" => TokenType::Else,
+        "false" => TokenType::False,
+        "for" => TokenType::For,
+        "fun" => TokenType::Fun,
+        "if" => TokenType::If,
+        "nil" => TokenType::Nil,
+        "or" => TokenType::Or,
+        "print" => TokenType::Print,
+        "return" => TokenType::Return,
+        "super" => TokenType::Super,
+        "this" => TokenType::This,
+        "true" => TokenType::True,
+        "var" => TokenType::Var,
+        "while" => TokenType::While,
+        _ => return None,
+    };
+    Some(token_type)
+}
 
-    let mut parser = Parser::new(tokens.clone());
-    if let Some(expression) = parser.parse() {
-        let mut printer = AstPrinter::new();
-        println!("\nAST: {}", printer.print(&expression));
-    }
+/// Whether `text` is a name the scanner could produce: identifier characters,
+/// in NFC, and not a reserved word.
+pub fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(is_identifier_start)
+        && chars.all(is_identifier_continue)
+        && is_nfc(text)
+        && keyword(text).is_none()
+}
+
+// identifier -> ( XID_Start | "_" ) XID_Continue*
+fn is_identifier_start(c: char) -> bool {
+    c == '_' || c.is_xid_start()
+}
 
-    // Keep token printing for debugging
-    println!("\nTokens:");
-    for token in tokens {
-        println!("{:?}", token);
+fn is_identifier_continue(c: char) -> bool {
+    c.is_xid_continue()
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = Result<Token, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            None
+        } else {
+            Some(self.scan_token())
+        }
     }
 }
@@ -0,0 +1,60 @@
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// An interned string. Symbols are a `u32` handle, so they are cheap to copy and
+/// compare, and the text behind them lives for the rest of the program.
+///
+/// The interner is per thread, so symbols are deliberately neither `Send` nor
+/// `Sync`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    id: u32,
+    _not_send: PhantomData<*const ()>,
+}
+
+#[derive(Default)]
+struct Interner {
+    ids: FxHashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::default();
+}
+
+impl Symbol {
+    /// Returns the symbol for `text`, copying it only the first time it is seen.
+    pub fn intern(text: &str) -> Symbol {
+        INTERNER.with_borrow_mut(|interner| {
+            if let Some(&symbol) = interner.ids.get(text) {
+                return symbol;
+            }
+            let text: &'static str = Box::leak(text.to_owned().into_boxed_str());
+            let symbol = Symbol {
+                id: interner.strings.len() as u32,
+                _not_send: PhantomData,
+            };
+            interner.strings.push(text);
+            interner.ids.insert(text, symbol);
+            symbol
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        INTERNER.with_borrow(|interner| interner.strings[self.id as usize])
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
@@ -1,6 +1,8 @@
+use crate::scanner::symbol::Symbol;
+use crate::scanner::trivia::TokenTrivia;
 use std::fmt;
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub enum TokenType {
     // Single-character tokens.
     LeftParen,
@@ -14,6 +16,8 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Question,
+    Colon,
     // One or two character tokens.
     Bang,
     BangEqual,
@@ -26,10 +30,14 @@ pub enum TokenType {
     // Literals.
     Identifier,
     String,
+    /// A string segment that ends where an interpolated `${` expression starts.
+    Interpolation,
     Number,
     // Keywords.
     And,
+    Break,
     Class,
+    Continue,
     Else,
     False,
     Fun,
@@ -44,6 +52,8 @@ pub enum TokenType {
     True,
     Var,
     While,
+    // Trivia.
+    DocComment,
 
     Eof,
 }
@@ -60,6 +70,8 @@ impl fmt::Display for TokenType {
             TokenType::Minus => write!(f, "Minus"),
             TokenType::Plus => write!(f, "Plus"),
             TokenType::Semicolon => write!(f, "Semicolon"),
+            TokenType::Question => write!(f, "Question"),
+            TokenType::Colon => write!(f, "Colon"),
             TokenType::Slash => write!(f, "Slash"),
             TokenType::Star => write!(f, "Star"),
             TokenType::Bang => write!(f, "Bang"),
@@ -72,9 +84,12 @@ impl fmt::Display for TokenType {
             TokenType::LessEqual => write!(f, "LessEqual"),
             TokenType::Identifier => write!(f, "Identifier"),
             TokenType::String => write!(f, "String"),
+            TokenType::Interpolation => write!(f, "Interpolation"),
             TokenType::Number => write!(f, "Number"),
             TokenType::And => write!(f, "And"),
+            TokenType::Break => write!(f, "Break"),
             TokenType::Class => write!(f, "Class"),
+            TokenType::Continue => write!(f, "Continue"),
             TokenType::Else => write!(f, "Else"),
             TokenType::False => write!(f, "False"),
             TokenType::Fun => write!(f, "Fun"),
@@ -89,31 +104,91 @@ impl fmt::Display for TokenType {
             TokenType::True => write!(f, "True"),
             TokenType::Var => write!(f, "Var"),
             TokenType::While => write!(f, "While"),
+            TokenType::DocComment => write!(f, "DocComment"),
             TokenType::Eof => write!(f, "Eof"),
         }
     }
 }
 
+/// Value of a literal token, decoded once by the scanner.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Number(f64),
+    String(Symbol),
+    Bool(bool),
+    Nil,
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Number(n) => write!(f, "{}", n),
+            Literal::String(s) => write!(f, "{}", s),
+            Literal::Bool(b) => write!(f, "{}", b),
+            Literal::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+/// Location of a token in the source: byte offsets of its first and one-past-last
+/// byte, plus the line and column (both 1-based) where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// The span from the start of `self` to the end of `end`, for a node made
+    /// of several tokens.
+    pub fn to(self, end: Span) -> Span {
+        Span {
+            end: end.end,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
-    pub lexeme: String,
-    pub literal: Option<String>,
-    pub line: usize,
+    pub lexeme: Symbol,
+    pub literal: Option<Literal>,
+    pub span: Span,
+    /// Surrounding whitespace and comments; only filled in by a lossless scan.
+    pub trivia: Option<Box<TokenTrivia>>,
 }
 
 impl Token {
     pub fn new(
         token_type: TokenType,
-        lexeme: String,
-        literal: Option<String>,
-        line: usize,
+        lexeme: Symbol,
+        literal: Option<Literal>,
+        span: Span,
     ) -> Self {
         Token {
             token_type,
             lexeme,
             literal,
-            line,
+            span,
+            trivia: None,
         }
     }
 }
@@ -136,6 +211,8 @@ impl fmt::Debug for TokenType {
             TokenType::Minus => write!(f, "Minus"),
             TokenType::Plus => write!(f, "Plus"),
             TokenType::Semicolon => write!(f, "Semicolon"),
+            TokenType::Question => write!(f, "Question"),
+            TokenType::Colon => write!(f, "Colon"),
             TokenType::Slash => write!(f, "Slash"),
             TokenType::Star => write!(f, "Star"),
             TokenType::Bang => write!(f, "Bang"),
@@ -148,9 +225,12 @@ impl fmt::Debug for TokenType {
             TokenType::LessEqual => write!(f, "LessEqual"),
             TokenType::Identifier => write!(f, "Identifier"),
             TokenType::String => write!(f, "String"),
+            TokenType::Interpolation => write!(f, "Interpolation"),
             TokenType::Number => write!(f, "Number"),
             TokenType::And => write!(f, "And"),
+            TokenType::Break => write!(f, "Break"),
             TokenType::Class => write!(f, "Class"),
+            TokenType::Continue => write!(f, "Continue"),
             TokenType::Else => write!(f, "Else"),
             TokenType::False => write!(f, "False"),
             TokenType::Fun => write!(f, "Fun"),
@@ -165,6 +245,7 @@ impl fmt::Debug for TokenType {
             TokenType::True => write!(f, "True"),
             TokenType::Var => write!(f, "Var"),
             TokenType::While => write!(f, "While"),
+            TokenType::DocComment => write!(f, "DocComment"),
             TokenType::Eof => write!(f, "Eof"),
         }
     }
@@ -0,0 +1,65 @@
+use crate::scanner::token::{Span, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// A run of spaces, tabs and carriage returns.
+    Whitespace,
+    Newline,
+    LineComment,
+    BlockComment,
+    /// Text the scanner consumed while reporting a lexical error.
+    Skipped,
+}
+
+/// A piece of source text that carries no meaning for the grammar. Only its
+/// span is kept; the text is read back from the source it was scanned from.
+#[derive(Debug, Clone)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+impl Trivia {
+    pub fn text<'src>(&self, source: &'src str) -> &'src str {
+        &source[self.span.start..self.span.end]
+    }
+}
+
+/// Trivia a lossless scan attaches to a token. Leading trivia is everything
+/// since the previous token's trailing trivia; trailing trivia runs up to and
+/// including the end of the token's line, stopping before any block comment.
+#[derive(Debug, Clone, Default)]
+pub struct TokenTrivia {
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+}
+
+impl Token {
+    /// Appends the token to `out` exactly as it was written in `source`, trivia
+    /// included. Tokens from a normal scan carry no trivia and append their
+    /// lexeme.
+    pub fn write_source(&self, source: &str, out: &mut String) {
+        match &self.trivia {
+            Some(trivia) => {
+                for piece in &trivia.leading {
+                    out.push_str(piece.text(source));
+                }
+                out.push_str(&source[self.span.start..self.span.end]);
+                for piece in &trivia.trailing {
+                    out.push_str(piece.text(source));
+                }
+            }
+            None => out.push_str(self.lexeme.as_str()),
+        }
+    }
+}
+
+/// Rebuilds the source text of a token stream scanned from `source`. For tokens
+/// from a lossless scan this reproduces `source` byte for byte.
+pub fn to_source(source: &str, tokens: &[Token]) -> String {
+    let mut out = String::with_capacity(source.len());
+    for token in tokens {
+        token.write_source(source, &mut out);
+    }
+    out
+}
@@ -0,0 +1,139 @@
+use crate::ast::arena::Ast;
+use crate::expression::expression::ExprId;
+use crate::scanner::token::Token;
+use std::rc::Rc;
+
+/// Identifies one statement in an `Ast`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StmtId(u32);
+
+impl StmtId {
+    pub(crate) fn from_index(index: usize) -> StmtId {
+        StmtId(u32::try_from(index).expect("too many statements"))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+pub enum Stmt {
+    Block {
+        statements: Vec<StmtId>,
+    },
+    Break {
+        keyword: Token,
+    },
+    /// `superclass` is always an `Expr::Variable` when present.
+    Class {
+        name: Token,
+        superclass: Option<ExprId>,
+        methods: Vec<Rc<Function>>,
+    },
+    Continue {
+        keyword: Token,
+    },
+    Expression {
+        expression: ExprId,
+    },
+    Function(Rc<Function>),
+    If {
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    },
+    Print {
+        expression: ExprId,
+    },
+    Return {
+        keyword: Token,
+        value: Option<ExprId>,
+    },
+    Var {
+        name: Token,
+        initializer: Option<ExprId>,
+    },
+    /// Also the target of `for` loops, which the parser desugars into a
+    /// `while` inside a block. `increment` is only set for those, and runs
+    /// after every iteration, including ones cut short by `continue`.
+    While {
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    },
+}
+
+/// A function declaration or lambda. It is shared, so the functions created
+/// from it at runtime can keep it alive without copying the body.
+pub struct Function {
+    /// `None` for a lambda; declarations and methods always have a name.
+    pub name: Option<Token>,
+    pub params: Vec<Token>,
+    pub body: Vec<StmtId>,
+}
+
+/// Every method gets the id of the node it visits first, like `ExprVisitor`.
+pub trait StmtVisitor<R> {
+    fn visit_block_stmt(&mut self, id: StmtId, statements: &[StmtId]) -> R;
+    fn visit_break_stmt(&mut self, id: StmtId, keyword: &Token) -> R;
+    fn visit_class_stmt(
+        &mut self,
+        id: StmtId,
+        name: &Token,
+        superclass: Option<ExprId>,
+        methods: &[Rc<Function>],
+    ) -> R;
+    fn visit_continue_stmt(&mut self, id: StmtId, keyword: &Token) -> R;
+    fn visit_expression_stmt(&mut self, id: StmtId, expression: ExprId) -> R;
+    fn visit_function_stmt(&mut self, id: StmtId, function: &Rc<Function>) -> R;
+    fn visit_if_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
+    ) -> R;
+    fn visit_print_stmt(&mut self, id: StmtId, expression: ExprId) -> R;
+    fn visit_return_stmt(&mut self, id: StmtId, keyword: &Token, value: Option<ExprId>) -> R;
+    fn visit_var_stmt(&mut self, id: StmtId, name: &Token, initializer: Option<ExprId>) -> R;
+    fn visit_while_stmt(
+        &mut self,
+        id: StmtId,
+        condition: ExprId,
+        body: StmtId,
+        increment: Option<ExprId>,
+    ) -> R;
+}
+
+impl StmtId {
+    /// Dispatches to the `visitor` method for this node in `ast`, which stays
+    /// borrowed during the call.
+    pub fn accept<R>(self, ast: &Ast, visitor: &mut dyn StmtVisitor<R>) -> R {
+        let id = self;
+        match &*ast.stmt(id) {
+            Stmt::Block { statements } => visitor.visit_block_stmt(id, statements),
+            Stmt::Break { keyword } => visitor.visit_break_stmt(id, keyword),
+            Stmt::Class {
+                name,
+                superclass,
+                methods,
+            } => visitor.visit_class_stmt(id, name, *superclass, methods),
+            Stmt::Continue { keyword } => visitor.visit_continue_stmt(id, keyword),
+            Stmt::Expression { expression } => visitor.visit_expression_stmt(id, *expression),
+            Stmt::Function(function) => visitor.visit_function_stmt(id, function),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => visitor.visit_if_stmt(id, *condition, *then_branch, *else_branch),
+            Stmt::Print { expression } => visitor.visit_print_stmt(id, *expression),
+            Stmt::Return { keyword, value } => visitor.visit_return_stmt(id, keyword, *value),
+            Stmt::Var { name, initializer } => visitor.visit_var_stmt(id, name, *initializer),
+            Stmt::While {
+                condition,
+                body,
+                increment,
+            } => visitor.visit_while_stmt(id, *condition, *body, *increment),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use jlox::dialect::dialect::Dialect;
+use jlox::scanner::scanner::{ScanResult, Scanner};
+use jlox::scanner::token::{Literal, TokenType};
+
+fn scan(source: &str, dialect: Dialect) -> ScanResult {
+    Scanner::new(source).with_dialect(dialect).scan_tokens()
+}
+
+// The whole literal is reported once, and nothing of it is left to scan.
+fn assert_strict_rejects(source: &str, message: &str) {
+    let ScanResult { tokens, errors } = scan(source, Dialect::Strict);
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages, [message]);
+    assert_eq!(
+        (errors[0].span.start, errors[0].span.end),
+        (0, source.len())
+    );
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token_type, TokenType::Eof);
+}
+
+fn extended_value(source: &str) -> f64 {
+    let ScanResult { tokens, errors } = scan(source, Dialect::Extended);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    match tokens[0].literal {
+        Some(Literal::Number(n)) => n,
+        ref other => panic!("expected a number, got {:?}", other),
+    }
+}
+
+#[test]
+fn strict_rejects_hexadecimal_literals() {
+    assert_strict_rejects("0x", "Hexadecimal literals require the extended dialect.");
+    assert_strict_rejects("0xFF", "Hexadecimal literals require the extended dialect.");
+}
+
+#[test]
+fn strict_rejects_exponents() {
+    assert_strict_rejects("1e5", "Exponents require the extended dialect.");
+}
+
+#[test]
+fn strict_rejects_digit_separators() {
+    assert_strict_rejects("1_000", "Digit separators require the extended dialect.");
+}
+
+#[test]
+fn extended_accepts_them() {
+    assert_eq!(extended_value("0xFF"), 255.0);
+    assert_eq!(extended_value("1e5"), 100_000.0);
+    assert_eq!(extended_value("1_000"), 1000.0);
+}
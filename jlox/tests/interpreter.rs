@@ -0,0 +1,50 @@
+use jlox::interpreter::interpreter::Interpreter;
+use jlox::parser::parser::Parser;
+use jlox::resolver::resolver::Resolver;
+use jlox::scanner::scanner::Scanner;
+use std::rc::Rc;
+
+/// MAX_DEPTH is sized for the 8 MiB main thread, and test threads get less.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Runs `source`, returning the message and line of any runtime error.
+fn run(source: &str) -> Result<(), (String, usize)> {
+    let source = source.to_string();
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let tokens = Scanner::new(&source).scan_tokens().tokens;
+            let mut interpreter = Interpreter::new();
+            let ast = Rc::clone(interpreter.ast());
+            let statements = Parser::new(&tokens, &ast).parse().expect("source parses");
+            Resolver::new(&mut interpreter)
+                .resolve(&statements)
+                .expect("source resolves");
+            interpreter
+                .interpret(&statements)
+                .map_err(|e| (e.message, e.token.span.line))
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+#[test]
+fn unbounded_recursion_is_a_stack_overflow() {
+    let error = run("fun f() {\n  f();\n}\nf();").expect_err("recursion never ends");
+    assert_eq!(error, ("Stack overflow.".to_string(), 2));
+}
+
+#[test]
+fn bounded_recursion_runs() {
+    run("fun count(n) { if (n > 0) count(n - 1); }\ncount(200);").expect("recursion ends");
+}
+
+#[test]
+fn long_chain_of_instances_is_dropped() {
+    run("class Node { init(next) { this.next = next; } }
+         var head = nil;
+         for (var i = 0; i < 300000; i = i + 1) head = Node(head);
+         head = nil;")
+    .expect("chain is built and dropped");
+}
@@ -0,0 +1,38 @@
+use jlox::ast::arena::Ast;
+use jlox::errors::parse_error::ParseError;
+use jlox::expression::expression::Expr;
+use jlox::parser::parser::Parser;
+use jlox::scanner::scanner::Scanner;
+use jlox::statement::statement::{Stmt, StmtId};
+
+fn parse(source: &str, ast: &Ast) -> Result<Vec<StmtId>, Vec<ParseError>> {
+    let tokens = Scanner::new(source).scan_tokens().tokens;
+    Parser::new(&tokens, ast).parse()
+}
+
+#[test]
+fn fun_at_the_start_of_a_statement_is_a_declaration() {
+    let errors =
+        parse("fun (a) { print a; };", &Ast::new()).expect_err("a declaration needs a name");
+    assert_eq!(errors[0].message, "Expect function name.");
+    assert_eq!(errors[0].token.lexeme.as_str(), "(");
+}
+
+#[test]
+fn parenthesized_lambda_can_be_called() {
+    let ast = Ast::new();
+    let statements = parse("(fun (a) { print a; })(1);", &ast).expect("source parses");
+    let [statement] = statements[..] else {
+        panic!("expected one statement");
+    };
+    let Stmt::Expression { expression } = *ast.stmt(statement) else {
+        panic!("expected an expression statement");
+    };
+    let Expr::Call { callee, .. } = *ast.expr(expression) else {
+        panic!("expected a call");
+    };
+    let Expr::Grouping { expression } = *ast.expr(callee) else {
+        panic!("expected a parenthesized callee");
+    };
+    assert!(matches!(*ast.expr(expression), Expr::Function(_)));
+}
@@ -0,0 +1,86 @@
+use jlox::errors::resolve_error::ResolveError;
+use jlox::interpreter::interpreter::Interpreter;
+use jlox::parser::parser::Parser;
+use jlox::resolver::resolver::Resolver;
+use jlox::scanner::scanner::Scanner;
+use jlox::statement::statement::StmtId;
+use std::rc::Rc;
+
+fn resolve(source: &str) -> Result<(Interpreter, Vec<StmtId>), Vec<ResolveError>> {
+    let tokens = Scanner::new(source).scan_tokens().tokens;
+    let mut interpreter = Interpreter::new();
+    let ast = Rc::clone(interpreter.ast());
+    let statements = Parser::new(&tokens, &ast).parse().expect("source parses");
+    Resolver::new(&mut interpreter).resolve(&statements)?;
+    Ok((interpreter, statements))
+}
+
+// A failed check in the program calls an undefined function, which stops it
+// with a runtime error.
+fn assert_runs(source: &str) {
+    let Ok((mut interpreter, statements)) = resolve(source) else {
+        panic!("source resolves");
+    };
+    if let Err(error) = interpreter.interpret(&statements) {
+        panic!("{}", error.message);
+    }
+}
+
+fn resolve_errors(source: &str) -> Vec<String> {
+    match resolve(source) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|e| e.message).collect(),
+    }
+}
+
+#[test]
+fn continue_in_a_for_loop_still_runs_the_increment() {
+    assert_runs(
+        "var total = 0;
+         var steps = 0;
+         for (var i = 0; i < 5; i = i + 1) {
+           steps = steps + 1;
+           if (steps > 10) break;
+           if (i == 2) continue;
+           total = total + i;
+         }
+         if (steps != 5) continueSkippedTheIncrement();
+         if (total != 8) continueDidNotSkipTheRest();",
+    );
+}
+
+#[test]
+fn break_leaves_only_the_innermost_loop() {
+    assert_runs(
+        "var outer = 0;
+         while (outer < 3) {
+           outer = outer + 1;
+           while (true) break;
+         }
+         if (outer != 3) breakLeftTheOuterLoop();",
+    );
+}
+
+#[test]
+fn break_outside_a_loop_is_an_error() {
+    assert_eq!(
+        resolve_errors("break;"),
+        ["Can't use 'break' outside of a loop."]
+    );
+}
+
+#[test]
+fn continue_outside_a_loop_is_an_error() {
+    assert_eq!(
+        resolve_errors("if (true) continue;"),
+        ["Can't use 'continue' outside of a loop."]
+    );
+}
+
+#[test]
+fn functions_do_not_see_the_enclosing_loop() {
+    assert_eq!(
+        resolve_errors("while (true) { fun f() { break; } }"),
+        ["Can't use 'break' outside of a loop."]
+    );
+}
@@ -0,0 +1,105 @@
+use jlox::ast::arena::Ast;
+use jlox::parser::parser::Parser;
+use jlox::scanner::scanner::Scanner;
+
+/// Far past any depth the native stack could take by recursion.
+const COPIES: usize = 100_000;
+
+/// The limits are sized for the 8 MiB main thread, and test threads get less.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Parses `source` with the limits `limits` sets, returning the error messages
+/// if it is rejected.
+fn parse_with(source: &str, limits: fn(Parser) -> Parser) -> Result<(), Vec<String>> {
+    let source = source.to_string();
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let tokens = Scanner::new(&source).scan_tokens().tokens;
+            let ast = Ast::new();
+            match limits(Parser::new(&tokens, &ast)).parse() {
+                Ok(_) => Ok(()),
+                Err(errors) => Err(errors.into_iter().map(|e| e.message).collect()),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+fn parse(source: &str) -> Result<(), Vec<String>> {
+    parse_with(source, |parser| parser)
+}
+
+// Parsing stops at the limit, so there is exactly one error.
+fn assert_rejected(source: &str, message: &str) {
+    let messages = parse(source).expect_err("input is rejected");
+    assert_eq!(messages, [message]);
+}
+
+#[test]
+fn nested_parentheses() {
+    assert_rejected(&"(".repeat(COPIES), "Expression nesting too deep.");
+}
+
+#[test]
+fn nested_unary_operators() {
+    assert_rejected(&"-".repeat(COPIES), "Expression nesting too deep.");
+}
+
+#[test]
+fn nested_blocks() {
+    assert_rejected(&"{".repeat(COPIES), "Statement nesting too deep.");
+}
+
+#[test]
+fn nested_function_declarations() {
+    assert_rejected(&"fun f() {".repeat(COPIES), "Statement nesting too deep.");
+}
+
+#[test]
+fn nested_lambdas() {
+    assert_rejected(&"(fun () {".repeat(COPIES), "Expression nesting too deep.");
+}
+
+#[test]
+fn nested_methods() {
+    assert_rejected(
+        &"class C { m() {".repeat(COPIES),
+        "Statement nesting too deep.",
+    );
+}
+
+#[test]
+fn long_operator_chain() {
+    let source = format!("print 1{};", " + 1".repeat(COPIES));
+    assert_rejected(&source, "Expression too long.");
+}
+
+#[test]
+fn chains_are_not_held_to_the_depth_limit() {
+    let source = format!("print 1{};", " + 1".repeat(1000));
+    assert!(parse(&source).is_ok());
+}
+
+#[test]
+fn chains_nested_in_the_left_operand_add_up() {
+    // Each group is the first operand of the chain around it, so the tree is
+    // as tall as all the chains together.
+    let mut source = "1".to_string();
+    for _ in 0..50 {
+        source = format!("({}{})", source, " + 1".repeat(100));
+    }
+    assert_rejected(&format!("print {};", source), "Expression too long.");
+}
+
+#[test]
+fn limits_are_configurable() {
+    let nested = format!("print {}1{};", "(".repeat(100), ")".repeat(100));
+    assert!(parse(&nested).is_ok());
+    assert!(parse_with(&nested, |parser| parser.with_max_depth(50)).is_err());
+
+    let chain = format!("print 1{};", " + 1".repeat(100));
+    assert!(parse(&chain).is_ok());
+    assert!(parse_with(&chain, |parser| parser.with_max_height(50)).is_err());
+}
@@ -0,0 +1,61 @@
+use jlox::ast::arena::Ast;
+use jlox::expression::expression::Expr;
+use jlox::parser::parser::Parser;
+use jlox::parser::rules::{ParseRule, ParseRules, Precedence};
+use jlox::scanner::scanner::Scanner;
+use jlox::scanner::token::TokenType;
+use jlox::statement::statement::{Stmt, StmtId};
+
+fn parse_with(source: &str, ast: &Ast, rules: ParseRules) -> Vec<StmtId> {
+    let tokens = Scanner::new(source).scan_tokens().tokens;
+    Parser::new(&tokens, ast)
+        .with_rules(rules)
+        .parse()
+        .expect("source parses")
+}
+
+// Whether `statement` prints a sum whose right operand is itself a sum.
+fn nests_to_the_right(ast: &Ast, statement: StmtId) -> bool {
+    let Stmt::Print { expression } = *ast.stmt(statement) else {
+        return false;
+    };
+    match *ast.expr(expression) {
+        Expr::Binary { left, right, .. } => {
+            matches!(*ast.expr(left), Expr::Literal { .. })
+                && matches!(*ast.expr(right), Expr::Binary { .. })
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn rule_defined_outside_the_crate_builds_nodes() {
+    // `+` made right-associative, using only the public parser API.
+    let mut rules = ParseRules::default();
+    rules.register(
+        TokenType::Plus,
+        ParseRule::new(
+            Some(|p| p.missing_left_operand()),
+            Some(|p, left| {
+                let operator = p.previous().clone();
+                let right = p.parse_precedence(p.operator_precedence())?;
+                Ok(p.add_expr(
+                    p.expr_span(left),
+                    Expr::Binary {
+                        left,
+                        operator,
+                        right,
+                    },
+                ))
+            }),
+            Precedence::Term,
+        ),
+    );
+
+    let source = "print 1 + 2 + 3;";
+    let ast = Ast::new();
+    let default = parse_with(source, &ast, ParseRules::default());
+    assert!(!nests_to_the_right(&ast, default[0]));
+    let custom = parse_with(source, &ast, rules);
+    assert!(nests_to_the_right(&ast, custom[0]));
+}
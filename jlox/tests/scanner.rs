@@ -0,0 +1,84 @@
+use jlox::errors::scan_error::ScanError;
+use jlox::scanner::scanner::{ScanResult, Scanner};
+use jlox::scanner::symbol::Symbol;
+use jlox::scanner::token::{Literal, Span, Token, TokenType};
+
+fn scan(source: &str) -> Vec<Token> {
+    let ScanResult { tokens, errors } = Scanner::new(source).scan_tokens();
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    tokens
+}
+
+fn scan_with_errors(source: &str) -> (Vec<TokenType>, Vec<ScanError>) {
+    let ScanResult { tokens, errors } = Scanner::new(source).scan_tokens();
+    (token_types(&tokens), errors)
+}
+
+fn token_types(tokens: &[Token]) -> Vec<TokenType> {
+    tokens.iter().map(|t| t.token_type).collect()
+}
+
+#[test]
+fn escape_sequences_are_decoded() {
+    let tokens = scan(r#""a\n\t\"\\\u{1F600}""#);
+    assert_eq!(
+        tokens[0].literal,
+        Some(Literal::String(Symbol::intern("a\n\t\"\\\u{1F600}")))
+    );
+    assert_eq!(tokens[0].lexeme.as_str(), r#""a\n\t\"\\\u{1F600}""#);
+}
+
+#[test]
+fn spans_cover_the_token_source() {
+    let source = "var s =\n  \"h\u{e9}\";";
+    let tokens = scan(source);
+    let string = &tokens[3];
+    assert_eq!(string.span, Span::new(10, 15, 2, 3));
+    assert_eq!(&source[string.span.start..string.span.end], "\"h\u{e9}\"");
+    assert_eq!(tokens[4].span, Span::new(15, 16, 2, 7));
+}
+
+#[test]
+fn unterminated_string_is_reported_at_its_opening_quote() {
+    let (types, errors) = scan_with_errors("print 1;\nprint \"abc");
+    assert_eq!(
+        types,
+        [
+            TokenType::Print,
+            TokenType::Number,
+            TokenType::Semicolon,
+            TokenType::Print,
+            TokenType::Eof
+        ]
+    );
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "Unterminated string.");
+    assert_eq!(errors[0].span, Span::new(15, 19, 2, 7));
+}
+
+#[test]
+fn scanning_continues_after_errors() {
+    let (types, errors) = scan_with_errors("print \"a\\qb\"; @ print 1;");
+    assert_eq!(
+        types,
+        [
+            TokenType::Print,
+            TokenType::Semicolon,
+            TokenType::Print,
+            TokenType::Number,
+            TokenType::Semicolon,
+            TokenType::Eof
+        ]
+    );
+    let messages: Vec<(&str, Span)> = errors
+        .iter()
+        .map(|e| (e.message.as_str(), e.span))
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            ("Unknown escape sequence '\\q'.", Span::new(8, 10, 1, 9)),
+            ("Unexpected character: @", Span::new(14, 15, 1, 15)),
+        ]
+    );
+}
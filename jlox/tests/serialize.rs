@@ -0,0 +1,193 @@
+use jlox::ast::arena::Ast;
+use jlox::ast::ast_format::AstFormat;
+use jlox::ast::serialize::{from_json, to_json};
+use jlox::parser::parser::{Parser, DEFAULT_MAX_DEPTH, DEFAULT_MAX_HEIGHT};
+use jlox::scanner::scanner::Scanner;
+use jlox::statement::statement::StmtId;
+
+fn parse_with(source: &str, ast: &Ast, max_depth: usize, max_height: usize) -> Vec<StmtId> {
+    let tokens = Scanner::new(source).scan_tokens().tokens;
+    Parser::new(&tokens, ast)
+        .with_max_depth(max_depth)
+        .with_max_height(max_height)
+        .parse()
+        .expect("source parses")
+}
+
+fn parse(source: &str, ast: &Ast) -> Vec<StmtId> {
+    parse_with(source, ast, DEFAULT_MAX_DEPTH, DEFAULT_MAX_HEIGHT)
+}
+
+fn load(json: &str, ast: &Ast) -> Result<Vec<StmtId>, String> {
+    from_json(json, ast, DEFAULT_MAX_DEPTH, DEFAULT_MAX_HEIGHT).map_err(|e| e.message)
+}
+
+// Prints `source` as parsed, and as loaded back from its JSON document.
+fn round_trip(source: &str) -> (String, String) {
+    let ast = Ast::new();
+    let statements = parse(source, &ast);
+    let json = to_json(&ast, &statements);
+
+    let loaded_ast = Ast::new();
+    let loaded = load(&json, &loaded_ast).expect("document loads");
+    (
+        AstFormat::Sexpr.print(&ast, &statements),
+        AstFormat::Sexpr.print(&loaded_ast, &loaded),
+    )
+}
+
+#[test]
+fn every_node_kind_round_trips() {
+    let source = r#"
+        class Base {
+            init(name) { this.name = name; }
+            greet() { return "hi ${this.name}, ${1 + 2}!"; }
+        }
+        class Derived < Base {
+            greet() { return super.greet(); }
+        }
+        fun apply(f, x) { return f(x); }
+        var square = fun (n) { return n * n; };
+        var a;
+        a = Derived("lox").greet();
+        print apply(square, -3) >= 9 and !false or nil;
+        print (1, 2) == 2 ? "comma" : "oops";
+        for (var i = 0; i < 10; i = i + 1) {
+            if (i == 2) continue; else if (i > 5) break;
+            print i / 2 - 0.5;
+        }
+        while (true) { break; }
+        {
+            var b = "block";
+            print b != a;
+        }
+        fun nothing() { return; }
+    "#;
+    let (original, loaded) = round_trip(source);
+    assert_eq!(loaded, original);
+}
+
+// Spans, operators and literals aren't all in the S-expression, so compare
+// the documents themselves too.
+#[test]
+fn loaded_tree_serializes_to_the_same_document() {
+    let ast = Ast::new();
+    let statements = parse("print 1 +\n  2;", &ast);
+    let json = to_json(&ast, &statements);
+    assert_eq!(to_json(&ast, &load(&json, &ast).unwrap()), json);
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let error = load(r#"{"version":2,"statements":[]}"#, &Ast::new()).unwrap_err();
+    assert_eq!(error, "Unsupported AST schema version 2, expected 1.");
+}
+
+#[test]
+fn unknown_operators_are_rejected() {
+    let json = r#"{"version":1,"statements":[{"kind":"Expression","expression":
+        {"kind":"Unary","operator":{"lexeme":"~"},
+         "right":{"kind":"Literal","value":1}}}]}"#;
+    let error = load(json, &Ast::new()).unwrap_err();
+    assert_eq!(error, "Unknown unary operator '~'.");
+}
+
+// Methods nest the document deepest, so they test the limits agree.
+#[test]
+fn whatever_the_parser_accepts_loads() {
+    let (max_depth, max_height) = (64, 1);
+    let source = "class C { m() {".repeat(max_depth) + &"} }".repeat(max_depth);
+    let ast = Ast::new();
+    let json = to_json(&ast, &parse_with(&source, &ast, max_depth, max_height));
+
+    assert!(from_json(&json, &Ast::new(), max_depth, max_height).is_ok());
+    let error = from_json(&json, &Ast::new(), max_depth - 1, max_height).unwrap_err();
+    assert_eq!(error.message, "AST nesting too deep.");
+}
+
+// A chain nests its document deeper than the depth limit alone allows.
+#[test]
+fn long_operator_chains_load() {
+    let ast = Ast::new();
+    let json = to_json(
+        &ast,
+        &parse(&format!("print 1{};", " + 1".repeat(300)), &ast),
+    );
+    assert!(load(&json, &Ast::new()).is_ok());
+}
+
+#[test]
+fn deeply_nested_documents_are_rejected() {
+    let copies = 100_000;
+    let json = format!(
+        r#"{{"version":1,"statements":[{{"kind":"Print","expression":{}1{}}}]}}"#,
+        r#"{"kind":"Unary","operator":{"lexeme":"-"},"right":"#.repeat(copies),
+        "}".repeat(copies)
+    );
+    let error = load(&json, &Ast::new()).unwrap_err();
+    assert_eq!(error, "AST nesting too deep.");
+}
+
+#[test]
+fn non_finite_numbers_round_trip() {
+    let json = r#"{"version":1,"statements":[
+        {"kind":"Print","expression":{"kind":"Literal","value":{"number":"inf"}}},
+        {"kind":"Print","expression":{"kind":"Literal","value":{"number":"-inf"}}},
+        {"kind":"Print","expression":{"kind":"Literal","value":{"number":"nan"}}}]}"#;
+    let ast = Ast::new();
+    let statements = load(json, &ast).expect("document loads");
+    let printed = AstFormat::Sexpr.print(&ast, &statements);
+    assert_eq!(printed, "(print inf)\n(print -inf)\n(print NaN)");
+
+    let loaded_ast = Ast::new();
+    let json = to_json(&ast, &statements);
+    let loaded = load(&json, &loaded_ast).expect("document loads");
+    assert_eq!(AstFormat::Sexpr.print(&loaded_ast, &loaded), printed);
+}
+
+// Serializes `source` with every name spelled `from` renamed to `to`.
+fn renamed(source: &str, from: &str, to: &str) -> String {
+    let ast = Ast::new();
+    let json = to_json(&ast, &parse(source, &ast));
+    json.replace(
+        &format!(r#""lexeme": "{}""#, from),
+        &format!(r#""lexeme": "{}""#, to),
+    )
+}
+
+// Were these loaded, `super.m()` would find the local instead of the class.
+#[test]
+fn reserved_words_are_rejected_as_names() {
+    let local = renamed(
+        "class B < A { m() { var s = 1; return super.m(); } }",
+        "s",
+        "super",
+    );
+    assert_eq!(
+        load(&local, &Ast::new()).unwrap_err(),
+        "Reserved word 'super' can't be used as a name."
+    );
+
+    let param = renamed("class B < A { m(s) { return super.m(); } }", "s", "super");
+    assert_eq!(
+        load(&param, &Ast::new()).unwrap_err(),
+        "Reserved word 'super' can't be used as a name."
+    );
+
+    let variable = renamed("print a;", "a", "this");
+    assert_eq!(
+        load(&variable, &Ast::new()).unwrap_err(),
+        "Reserved word 'this' can't be used as a name."
+    );
+}
+
+#[test]
+fn names_the_scanner_could_not_produce_are_rejected() {
+    for name in ["", "1a", "a b", "a-b"] {
+        let json = renamed("var a;", "a", name);
+        assert_eq!(
+            load(&json, &Ast::new()).unwrap_err(),
+            format!("'{}' is not a valid name.", name)
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use jlox::scanner::scanner::{ScanResult, Scanner};
+use jlox::scanner::trivia::to_source;
+
+fn assert_round_trips(source: &str) {
+    let ScanResult { tokens, errors } = Scanner::new(source).with_trivia(true).scan_tokens();
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    assert_eq!(to_source(source, &tokens), source);
+}
+
+#[test]
+fn crlf_line_endings() {
+    assert_round_trips("var a = 1;\r\nprint a;\r\n\r\n");
+}
+
+#[test]
+fn tabs_and_trailing_spaces() {
+    assert_round_trips("\tvar a\t=\t1;  \n\t\tprint a;\t\n");
+}
+
+#[test]
+fn nested_block_comments() {
+    assert_round_trips("/* outer /* inner\n */ still outer */ print 1; /* after */\n");
+}
+
+#[test]
+fn doc_comments() {
+    assert_round_trips("/// Adds one.\r\n/// Twice.\nvar one = 1; // not a doc comment\n");
+}
+
+#[test]
+fn no_trailing_newline() {
+    assert_round_trips("print \"a\\tb\";\n// last line");
+}
+
+#[test]
+fn normalized_identifiers_keep_their_spelling() {
+    assert_round_trips("var cafe\u{301} = 1;\nprint cafe\u{301};\n");
+}